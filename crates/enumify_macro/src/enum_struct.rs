@@ -1,26 +1,132 @@
-use std::collections::HashSet;
-
-use proc_macro2::{TokenStream, TokenTree};
-use quote::{format_ident, quote};
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::{
+    ext::IdentExt,
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
     spanned::Spanned,
     token::Comma,
-    Attribute, Data, DeriveInput, Field, Fields, Ident, Path, Token, Type,
-    Visibility,
+    Attribute, Data, DeriveInput, Field, Fields, Ident, Member, Path, Token,
+    Type, Variant, Visibility,
 };
 
 const RENAME_ATTRIBUTE: &str = "enumify_rename";
 const SKIP_WRAP_ATTRIBUTE: &str = "enumify_skip_wrap";
 const WRAP_ATTRIBUTE: &str = "enumify_wrap";
+const NESTED_ATTRIBUTE: &str = "enumify_nested";
+const SKIP_ATTRIBUTE: &str = "enumify_skip";
+const FORWARD_ATTRIBUTE: &str = "enumify_attr";
 const CFG_ATTRIBUTE: &str = "cfg";
+const CONTEXT_ATTRIBUTE: &str = "enumify_context";
+const ASYNC_ATTRIBUTE: &str = "enumify_async";
+const FROM_ENV_ATTRIBUTE: &str = "enumify_from_env";
+const CLAP_ATTRIBUTE: &str = "enumify_clap";
+const SKIP_PARSE_ATTRIBUTE: &str = "enumify_skip_parse";
 
 struct FieldOptions {
     wrapping_behavior: bool,
+    is_base_enum: bool,
+    in_variant: bool,
     cfg_attribute: Option<Attribute>,
-    new_type: Option<TokenTree>,
-    field_ident: TokenStream,
+    new_type: Option<TokenStream>,
+    field_ident: Member,
+    new_field_ident: Member,
+    forwarded_attributes: Vec<Attribute>,
+    /// Whether the field is left unset by the layers parsed from the
+    /// environment or the CLI
+    skip_parse: bool,
+}
+
+impl FieldOptions {
+    /// This field of `root`, which is the base when named `t` and a
+    /// generated structure otherwise. Both differ for tuple structures with
+    /// skipped fields.
+    fn member(&self, root: &str) -> &Member {
+        if root == "t" {
+            &self.field_ident
+        } else {
+            &self.new_field_ident
+        }
+    }
+
+    /// The binding introduced for this field when matching an enum variant
+    /// held by `root`, e.g. `self_bar` for `Self::Foo { bar: self_bar }`.
+    fn binding(&self, root: &str) -> Ident {
+        match &self.field_ident {
+            Member::Named(ident) => format_ident!("{root}_{}", ident.unraw()),
+            Member::Unnamed(index) => format_ident!("{root}_{}", index.index),
+        }
+    }
+
+    /// How the generated code reaches this field of `root`: a plain field
+    /// access for structs, or the binding of the matched enum variant.
+    /// `by_ref` tells whether `root` is matched through a reference, in which
+    /// case the binding is dereferenced so both forms can be assigned to.
+    /// `destructured` tells whether structs are destructured into bindings
+    /// as well.
+    fn access(
+        &self,
+        root: &str,
+        by_ref: bool,
+        destructured: bool,
+    ) -> TokenStream {
+        if !self.in_variant && !destructured {
+            let ident = self.member(root);
+            let root = Ident::new(root, Span::call_site());
+            return quote! { #root.#ident };
+        }
+
+        let binding = self.binding(root);
+        if by_ref {
+            quote! { (*#binding) }
+        } else {
+            quote! { #binding }
+        }
+    }
+
+    /// The type of this field in the generated structure, before it gets
+    /// wrapped in the target enum.
+    fn unwrapped_type(&self, old_field: &Field) -> TokenStream {
+        if let Some(t) = &self.new_type {
+            quote! {#t}
+        } else {
+            let t = &old_field.ty;
+            quote! {#t}
+        }
+    }
+
+    /// The name of this field, as reported in errors.
+    fn name(&self) -> String {
+        match &self.field_ident {
+            Member::Named(ident) => ident.unraw().to_string(),
+            Member::Unnamed(index) => index.index.to_string(),
+        }
+    }
+
+    /// Whether the field is neither wrapped nor nested, in which case every
+    /// layer sets it.
+    fn is_plain(&self) -> bool {
+        !self.wrapping_behavior && !self.is_base_enum && self.new_type.is_none()
+    }
+
+    /// The `cfg` attribute enabled exactly when this field is disabled.
+    fn negated_cfg_attribute(&self) -> Option<TokenStream> {
+        let cfg_attr = self.cfg_attribute.as_ref()?;
+        let predicate = match &cfg_attr.meta {
+            syn::Meta::List(list) => &list.tokens,
+            _ => return None,
+        };
+        Some(quote! { #[cfg(not(#predicate))] })
+    }
+
+    /// The field pattern binding this field of an enum variant held by
+    /// `root`.
+    fn pattern(&self, root: &str) -> TokenStream {
+        let cfg_attr = &self.cfg_attribute;
+        let ident = self.member(root);
+        let binding = self.binding(root);
+        quote! { #cfg_attr #ident: #binding }
+    }
 }
 
 trait EnumFieldVisitor {
@@ -31,16 +137,857 @@ trait EnumFieldVisitor {
         new_field: &mut Field,
         field_options: &FieldOptions,
     );
+
+    /// Called instead of `visit` for the fields marked `enumify_skip`, which
+    /// are left out of the generated structure.
+    fn visit_skipped(
+        &mut self,
+        _global_options: &GlobalOptions,
+        _old_field: &Field,
+        _field_options: &FieldOptions,
+    ) {
+    }
+
+    /// Called once all the fields of `variant` have been visited, when the
+    /// macro is used on an enum.
+    fn end_variant(&mut self, _variant: &Variant) {}
+}
+
+/// The `Applicable`-like traits generated for every structure, which only
+/// differ in how fields are resolved and how failures are reported.
+#[derive(Clone)]
+enum ApplyFlavor {
+    /// `Applicable::apply_to`
+    Infallible,
+    /// `TryApplicable::try_apply_to`, which reports the path of the field
+    /// that could not be resolved
+    Fallible,
+    /// `ApplicableWith::apply_to_with`, which threads a context of the given
+    /// type down to every field
+    Contextual(Box<Type>),
+    /// `TryApplicableWith::try_apply_to_with`, the fallible counterpart of
+    /// `Contextual`
+    FallibleContextual(Box<Type>),
+    /// `AsyncApplicable::apply_to_async`, which resolves the fields of a
+    /// structure concurrently
+    Async,
+    /// `Applicable::apply_to_tracked`, which records the dotted path of every
+    /// field it sets. Generated along with `Infallible`, within its
+    /// implementation.
+    Tracked,
 }
 
 struct GenerateApplicableImplVisitor {
+    orig_name: Ident,
+    generic_params: Vec<Ident>,
+    flavor: ApplyFlavor,
+    acc_bounds: Vec<TokenStream>,
     acc_concrete: TokenStream,
+    acc_self_pattern: TokenStream,
+    acc_base_pattern: TokenStream,
+    acc_replacement: TokenStream,
+    acc_arms: TokenStream,
+    acc_replacement_arms: TokenStream,
+    acc_join: TokenStream,
+    variant_count: usize,
+    /// Items added to the implementation, i.e. the `Tracked` method within
+    /// the `Infallible` implementation.
+    extra_items: TokenStream,
 }
 
 impl GenerateApplicableImplVisitor {
-    fn new() -> Self {
+    fn new(orig: &DeriveInput, flavor: ApplyFlavor) -> Self {
         GenerateApplicableImplVisitor {
+            orig_name: orig.ident.clone(),
+            generic_params: generic_params(orig),
+            flavor,
+            acc_bounds: Vec::new(),
             acc_concrete: quote! {},
+            acc_self_pattern: quote! {},
+            acc_base_pattern: quote! {},
+            acc_replacement: quote! {},
+            acc_arms: quote! {},
+            acc_replacement_arms: quote! {},
+            acc_join: quote! {},
+            variant_count: 0,
+            extra_items: quote! {},
+        }
+    }
+
+    fn get_implementation(
+        mut self,
+        orig: &DeriveInput,
+        new: &DeriveInput,
+    ) -> TokenStream {
+        let mut generics = orig.generics.clone();
+        generics.make_where_clause().predicates.extend(
+            self.acc_bounds.drain(..).map(|b| -> syn::WherePredicate {
+                syn::parse_quote! { #b }
+            }),
+        );
+        let (impl_generics, ty_generics, where_clause) =
+            generics.split_for_impl();
+        let orig_name = &orig.ident;
+        let new_name = &new.ident;
+        let flavor = self.flavor.clone();
+        let extra_items = std::mem::take(&mut self.extra_items);
+
+        let is_enum = matches!(orig.data, Data::Enum(_));
+        let is_empty_enum = is_enum && self.variant_count == 0;
+        let body = if is_enum {
+            self.get_enum_body()
+        } else if let ApplyFlavor::Async = flavor {
+            // Destructure both sides so that every field can be borrowed by
+            // its own future
+            let self_pattern = std::mem::take(&mut self.acc_self_pattern);
+            let base_pattern = std::mem::take(&mut self.acc_base_pattern);
+            let body = self.take_body();
+            quote! {
+                let Self { #self_pattern } = self;
+                let #orig_name { #base_pattern .. } = t;
+                #body
+            }
+        } else {
+            self.acc_concrete
+        };
+
+        // Matching on an empty enum already diverges
+        let ok = if is_empty_enum {
+            quote! {}
+        } else {
+            quote! { Ok(()) }
+        };
+        match flavor {
+            ApplyFlavor::Infallible => quote! {
+                impl #impl_generics enumify_struct::Applicable for #new_name #ty_generics #where_clause {
+                    type Base = #orig_name #ty_generics;
+
+                    fn apply_to(self, t: &mut Self::Base) {
+                        #body
+                    }
+
+                    #extra_items
+                }
+            },
+            ApplyFlavor::Fallible => quote! {
+                impl #impl_generics enumify_struct::TryApplicable for #new_name #ty_generics #where_clause {
+                    fn try_apply_to(
+                        self,
+                        t: &mut Self::Base,
+                    ) -> Result<(), enumify_struct::ResolveError> {
+                        #body
+                        #ok
+                    }
+                }
+            },
+            ApplyFlavor::Contextual(ctx) => quote! {
+                impl #impl_generics enumify_struct::ApplicableWith<#ctx> for #new_name #ty_generics #where_clause {
+                    type Base = #orig_name #ty_generics;
+
+                    fn apply_to_with(self, t: &mut Self::Base, ctx: &#ctx) {
+                        #body
+                    }
+                }
+            },
+            ApplyFlavor::FallibleContextual(ctx) => quote! {
+                impl #impl_generics enumify_struct::TryApplicableWith<#ctx> for #new_name #ty_generics #where_clause {
+                    fn try_apply_to_with(
+                        self,
+                        t: &mut <Self as enumify_struct::ApplicableWith<#ctx>>::Base,
+                        ctx: &#ctx,
+                    ) -> Result<(), enumify_struct::ResolveError> {
+                        #body
+                        #ok
+                    }
+                }
+            },
+            ApplyFlavor::Async => quote! {
+                impl #impl_generics enumify_struct::AsyncApplicable for #new_name #ty_generics #where_clause {
+                    type Base = #orig_name #ty_generics;
+
+                    async fn apply_to_async(self, t: &mut Self::Base) {
+                        #body
+                    }
+                }
+            },
+            ApplyFlavor::Tracked => quote! {
+                fn apply_to_tracked(
+                    self,
+                    t: &mut Self::Base,
+                    path: &str,
+                    record: &mut dyn FnMut(&str),
+                ) {
+                    #body
+                }
+            },
+        }
+    }
+
+    /// Takes the statements applying the fields visited so far. In the async
+    /// flavor, those only define one future per field, which are then
+    /// awaited together.
+    fn take_body(&mut self) -> TokenStream {
+        let concrete = std::mem::take(&mut self.acc_concrete);
+        let join = std::mem::take(&mut self.acc_join);
+        if join.is_empty() {
+            concrete
+        } else {
+            quote! {
+                #concrete
+                #join.await;
+            }
+        }
+    }
+
+    /// Applies field-wise when both sides hold the same variant, and
+    /// otherwise replaces the base with a value built from this variant only.
+    fn get_enum_body(self) -> TokenStream {
+        if self.variant_count == 0 {
+            return quote! { match self {} };
+        }
+
+        let arms = self.acc_arms;
+        let replacement_arms = self.acc_replacement_arms;
+        let fallback = if self.variant_count > 1 {
+            // Recorded before the fields of the new variant, so that those
+            // of the previous one are forgotten
+            let record = match &self.flavor {
+                ApplyFlavor::Tracked => quote! { record(path); },
+                _ => quote! {},
+            };
+            quote! {
+                (layer, t) => {
+                    #record
+                    *t = match layer {
+                        #replacement_arms
+                    };
+                }
+            }
+        } else {
+            quote! {}
+        };
+
+        quote! {
+            match (self, t) {
+                #arms
+                #fallback
+            }
+        }
+    }
+
+    /// Whether `tokens` mention a type or const parameter of the structure.
+    fn is_generic(&self, tokens: TokenStream) -> bool {
+        is_generic(&self.generic_params, tokens)
+    }
+
+    /// The trait nested enumified structures are bound to implement when
+    /// they are generic.
+    fn nested_bound(&self, base: &Type) -> TokenStream {
+        match &self.flavor {
+            ApplyFlavor::Infallible | ApplyFlavor::Tracked => {
+                quote! { enumify_struct::Applicable<Base = #base> }
+            }
+            ApplyFlavor::Fallible => {
+                quote! { enumify_struct::TryApplicable<Base = #base> }
+            }
+            ApplyFlavor::Contextual(ctx) => {
+                quote! { enumify_struct::ApplicableWith<#ctx, Base = #base> }
+            }
+            ApplyFlavor::FallibleContextual(ctx) => quote! {
+                enumify_struct::TryApplicableWith<#ctx, Base = #base>
+            },
+            ApplyFlavor::Async => {
+                quote! { enumify_struct::AsyncApplicable<Base = #base> }
+            }
+        }
+    }
+
+    /// Collects the bounds the nested fields mentioning generic parameters
+    /// need for the implementation to hold. Wrapped fields need none, as
+    /// they are resolved the same way as in non-generic structures, through
+    /// whichever trait the target enum implements: when it only does so for
+    /// some types, the structure itself has to be bound accordingly.
+    fn add_bounds(
+        &mut self,
+        old_field: &Field,
+        new_field: &Field,
+        field_options: &FieldOptions,
+    ) {
+        // Bounds cannot be conditionally compiled
+        if field_options.cfg_attribute.is_some()
+            || !self.is_generic(new_field.ty.to_token_stream())
+        {
+            return;
+        }
+
+        let unwrapped = field_options.unwrapped_type(old_field);
+        if field_options.new_type.is_some() {
+            let base = if field_options.is_base_enum {
+                get_wrapped_type(&old_field.ty)
+            } else {
+                Some(&old_field.ty)
+            };
+            let Some(base) = base else {
+                return;
+            };
+            let bound = self.nested_bound(base);
+            self.acc_bounds.push(quote! { #unwrapped: #bound });
+            if field_options.is_base_enum && !field_options.wrapping_behavior {
+                self.acc_bounds.push(quote! { #base: TryFrom<#unwrapped> });
+            }
+            if field_options.in_variant {
+                self.acc_bounds.push(quote! { #base: Default });
+            }
+        }
+    }
+
+    /// Resolves `src`, an enum wrapping a `ty`, to `None` when it leaves the
+    /// field unset. `or_unset` resolves it to a `ty` instead, for fields that
+    /// have to be given a value anyway. Target enums that cannot resolve a
+    /// `ty` are reported at `span`, the type of the field.
+    fn resolve(
+        &self,
+        ty: &TokenStream,
+        src: &TokenStream,
+        name: &str,
+        or_unset: bool,
+        span: Span,
+    ) -> TokenStream {
+        let suffix = if or_unset { "_or_unset" } else { "" };
+        // Unimplemented traits are reported at the argument, hence the local
+        match &self.flavor {
+            ApplyFlavor::Infallible | ApplyFlavor::Tracked => {
+                let resolve = format_ident!("resolve{suffix}");
+                quote_spanned! {span=> {
+                    let field = #src;
+                    enumify_struct::__private::Resolve::<#ty, _>::#resolve(field)
+                }}
+            }
+            ApplyFlavor::Fallible => {
+                let resolve = format_ident!("try_resolve{suffix}");
+                let resolved = quote_spanned! {span=> {
+                    let field = #src;
+                    enumify_struct::__private::TryResolve::<#ty, _>::#resolve(field)
+                }};
+                quote! {
+                    #resolved
+                        .map_err(|e| enumify_struct::ResolveError::new(#name, e))?
+                }
+            }
+            ApplyFlavor::Contextual(ctx) => {
+                let resolve = format_ident!("resolve_with{suffix}");
+                let ctx_arg = quote! { ctx };
+                quote_spanned! {span=> {
+                    let field = #src;
+                    enumify_struct::__private::ResolveWith::<#ty, #ctx, _>::#resolve(field, #ctx_arg)
+                }}
+            }
+            ApplyFlavor::FallibleContextual(ctx) => {
+                let resolve = format_ident!("try_resolve_with{suffix}");
+                let ctx_arg = quote! { ctx };
+                let resolved = quote_spanned! {span=> {
+                    let field = #src;
+                    enumify_struct::__private::TryResolveWith::<#ty, #ctx, _>::#resolve(field, #ctx_arg)
+                }};
+                quote! {
+                    #resolved
+                        .map_err(|e| enumify_struct::ResolveError::new(#name, e))?
+                }
+            }
+            ApplyFlavor::Async => {
+                let resolve = format_ident!("resolve_async{suffix}");
+                let resolved = quote_spanned! {span=> {
+                    let field = #src;
+                    enumify_struct::__private::ResolveAsync::<#ty, _>::#resolve(field)
+                }};
+                quote! { #resolved.await }
+            }
+        }
+    }
+
+    /// Applies the nested enumified structure `src` to `dst`, a mutable
+    /// reference to its base.
+    fn apply_nested(
+        &self,
+        src: &TokenStream,
+        dst: &TokenStream,
+        name: &str,
+    ) -> TokenStream {
+        match &self.flavor {
+            ApplyFlavor::Infallible => quote! {
+                enumify_struct::Applicable::apply_to(#src, #dst)
+            },
+            ApplyFlavor::Fallible => quote! {
+                enumify_struct::TryApplicable::try_apply_to(#src, #dst)
+                    .map_err(|e| e.within(#name))?
+            },
+            ApplyFlavor::Contextual(ctx) => quote! {
+                enumify_struct::ApplicableWith::<#ctx>::apply_to_with(#src, #dst, ctx)
+            },
+            ApplyFlavor::FallibleContextual(ctx) => quote! {
+                enumify_struct::TryApplicableWith::<#ctx>::try_apply_to_with(#src, #dst, ctx)
+                    .map_err(|e| e.within(#name))?
+            },
+            ApplyFlavor::Async => quote! {
+                enumify_struct::AsyncApplicable::apply_to_async(#src, #dst).await
+            },
+            ApplyFlavor::Tracked => quote! {
+                enumify_struct::Applicable::apply_to_tracked(
+                    #src,
+                    #dst,
+                    &enumify_struct::__private::field_path(path, #name),
+                    record
+                )
+            },
+        }
+    }
+
+    /// Records that the field `name` was set, in the `Tracked` flavor.
+    fn record(&self, name: &str) -> TokenStream {
+        match &self.flavor {
+            ApplyFlavor::Tracked => quote! {
+                record(&enumify_struct::__private::field_path(path, #name));
+            },
+            _ => quote! {},
+        }
+    }
+
+    /// Builds the base of the nested enumified structure `src` from its
+    /// `Default` value.
+    fn build_nested(&self, src: &TokenStream, name: &str) -> TokenStream {
+        match &self.flavor {
+            ApplyFlavor::Infallible => quote! {
+                enumify_struct::Applicable::build(#src, Default::default())
+            },
+            ApplyFlavor::Fallible => quote! {
+                enumify_struct::TryApplicable::try_build(#src, Default::default())
+                    .map_err(|e| e.within(#name))?
+            },
+            ApplyFlavor::Contextual(ctx) => quote! {
+                enumify_struct::ApplicableWith::<#ctx>::build_with(
+                    #src,
+                    Default::default(),
+                    ctx,
+                )
+            },
+            ApplyFlavor::FallibleContextual(ctx) => quote! {
+                enumify_struct::TryApplicableWith::<#ctx>::try_build_with(
+                    #src,
+                    Default::default(),
+                    ctx,
+                )
+                .map_err(|e| e.within(#name))?
+            },
+            ApplyFlavor::Async => quote! {
+                enumify_struct::AsyncApplicable::build_async(#src, Default::default()).await
+            },
+            ApplyFlavor::Tracked => {
+                let apply = self.apply_nested(src, &quote! { &mut base }, name);
+                quote! {{
+                    let mut base = Default::default();
+                    #apply;
+                    base
+                }}
+            }
+        }
+    }
+
+    fn get_incremental_setter_concrete(
+        &self,
+        field_options: &FieldOptions,
+        src: &TokenStream,
+        dst: &TokenStream,
+        resolved: &TokenStream,
+    ) -> TokenStream {
+        let name = field_options.name();
+        let is_wrapped = field_options.wrapping_behavior;
+        let is_nested = field_options.new_type.is_some();
+        let is_base_enum = field_options.is_base_enum;
+
+        let record = self.record(&name);
+
+        match (is_base_enum, is_wrapped, is_nested) {
+            (true, false, true) => {
+                let existing = quote! { existing };
+                let apply = self.apply_nested(src, &existing, &name);
+                quote! {
+                    if let Some(existing) = &mut #dst {
+                        #apply;
+                    } else {
+                        #dst = #src.try_into().ok();
+                        #record
+                    }
+                }
+            }
+            (true, false, false) => quote! {
+                #dst = #src;
+                #record
+            },
+            (false, false, true) => {
+                let apply =
+                    self.apply_nested(src, &quote! { &mut #dst }, &name);
+                quote! { #apply; }
+            }
+            (false, false, false) => quote! { #dst = #src; #record },
+            (true, true, true) => {
+                let apply = self.apply_nested(
+                    &quote! { inner },
+                    &quote! { target },
+                    &name,
+                );
+                quote! { if let (Some(inner), Some(target)) = (#src, &mut #dst) { #apply; } }
+            }
+            (false, true, true) => {
+                let apply = self.apply_nested(
+                    &quote! { inner },
+                    &quote! { &mut #dst },
+                    &name,
+                );
+                quote! {
+                    if let Some(inner) = #resolved {
+                        #apply;
+                    }
+                }
+            }
+            (_, true, false) => quote! {
+                if let Some(value) = #resolved {
+                    #dst = value;
+                    #record
+                }
+            },
+        }
+    }
+
+    /// The value of a field when the base has to be built from scratch, i.e.
+    /// when an enum layer holds a different variant than its base. Nested
+    /// structures are then applied to their `Default` value.
+    fn get_replacement_concrete(
+        &self,
+        field_options: &FieldOptions,
+        src: &TokenStream,
+        resolved: &TokenStream,
+        resolved_or_unset: &TokenStream,
+    ) -> TokenStream {
+        let name = field_options.name();
+        let is_wrapped = field_options.wrapping_behavior;
+        let is_nested = field_options.new_type.is_some();
+        let is_base_enum = field_options.is_base_enum;
+
+        match (is_base_enum, is_wrapped, is_nested) {
+            (true, _, true) => {
+                let field = quote! { field };
+                let setter = self.get_incremental_setter_concrete(
+                    field_options,
+                    src,
+                    &field,
+                    resolved,
+                );
+                quote! {{
+                    let mut field = Default::default();
+                    #setter
+                    field
+                }}
+            }
+            (false, false, true) => self.build_nested(src, &name),
+            (false, true, true) => {
+                let build = self.build_nested(&quote! { inner }, &name);
+                quote! {
+                    match #resolved {
+                        Some(inner) => #build,
+                        None => Default::default(),
+                    }
+                }
+            }
+            // Replacing the variant sets every field, even those left unset
+            (_, false, false) => {
+                let record = self.record(&name);
+                quote! {{ #record #src }}
+            }
+            (_, true, false) => {
+                let record = self.record(&name);
+                quote! {{ #record #resolved_or_unset }}
+            }
+        }
+    }
+}
+
+impl EnumFieldVisitor for GenerateApplicableImplVisitor {
+    fn visit(
+        &mut self,
+        _global_options: &GlobalOptions,
+        old_field: &mut Field,
+        new_field: &mut Field,
+        field_options: &FieldOptions,
+    ) {
+        let cfg_attr = &field_options.cfg_attribute;
+        let is_async = matches!(self.flavor, ApplyFlavor::Async);
+        self.add_bounds(old_field, new_field, field_options);
+
+        let src = field_options.access("self", false, is_async);
+        let dst = field_options.access("t", true, is_async);
+        let unwrapped = field_options.unwrapped_type(old_field);
+        let name = field_options.name();
+        let span = old_field.ty.span();
+        let resolved = self.resolve(&unwrapped, &src, &name, false, span);
+        let inc_concrete = self.get_incremental_setter_concrete(
+            field_options,
+            &src,
+            &dst,
+            &resolved,
+        );
+
+        let acc_concrete = &self.acc_concrete;
+        if is_async {
+            // Fields disabled by a `cfg` still get a future, so that joining
+            // them does not depend on which fields are enabled
+            let future =
+                format_ident!("{}_future", field_options.binding("self"));
+            let negated_cfg_attr = field_options.negated_cfg_attribute();
+            let disabled = negated_cfg_attr.map(|negated_cfg_attr| {
+                quote! {
+                    #negated_cfg_attr
+                    let #future = std::future::ready(());
+                }
+            });
+            self.acc_concrete = quote! {
+                #acc_concrete
+
+                #cfg_attr
+                let #future = async move { #inc_concrete };
+                #disabled
+            };
+
+            let acc_join = &self.acc_join;
+            self.acc_join = if acc_join.is_empty() {
+                quote! { #future }
+            } else {
+                quote! {
+                    enumify_struct::__private::futures::future::join(#future, #acc_join)
+                }
+            };
+        } else {
+            self.acc_concrete = quote! {
+                #acc_concrete
+
+                #cfg_attr
+                { #inc_concrete }
+            };
+        }
+
+        if field_options.in_variant || is_async {
+            let self_pattern = field_options.pattern("self");
+            let base_pattern = field_options.pattern("t");
+
+            let acc_self_pattern = &self.acc_self_pattern;
+            let acc_base_pattern = &self.acc_base_pattern;
+            self.acc_self_pattern = quote! { #acc_self_pattern #self_pattern, };
+            self.acc_base_pattern = quote! { #acc_base_pattern #base_pattern, };
+        }
+
+        if field_options.in_variant {
+            let ident = &field_options.field_ident;
+            let resolved_or_unset =
+                self.resolve(&unwrapped, &src, &name, true, span);
+            let replacement = self.get_replacement_concrete(
+                field_options,
+                &src,
+                &resolved,
+                &resolved_or_unset,
+            );
+
+            let acc_replacement = &self.acc_replacement;
+            self.acc_replacement = quote! {
+                #acc_replacement #cfg_attr #ident: #replacement,
+            };
+        }
+    }
+
+    fn visit_skipped(
+        &mut self,
+        _global_options: &GlobalOptions,
+        old_field: &Field,
+        field_options: &FieldOptions,
+    ) {
+        // Skipped fields are left untouched, unless the base gets replaced
+        // by another variant
+        if !field_options.in_variant {
+            return;
+        }
+
+        let cfg_attr = &field_options.cfg_attribute;
+        let ident = &field_options.field_ident;
+        let ty = &old_field.ty;
+        if cfg_attr.is_none() && self.is_generic(ty.to_token_stream()) {
+            self.acc_bounds.push(quote! { #ty: Default });
+        }
+
+        let acc_replacement = &self.acc_replacement;
+        self.acc_replacement = quote! {
+            #acc_replacement #cfg_attr #ident: Default::default(),
+        };
+    }
+
+    fn end_variant(&mut self, variant: &Variant) {
+        let orig_name = self.orig_name.clone();
+        let ident = &variant.ident;
+        let cfg_attrs = variant
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident(CFG_ATTRIBUTE));
+        let cfg_attrs = quote! { #(#cfg_attrs)* };
+
+        let acc_concrete = self.take_body();
+        let self_pattern = std::mem::take(&mut self.acc_self_pattern);
+        let base_pattern = std::mem::take(&mut self.acc_base_pattern);
+        let replacement = std::mem::take(&mut self.acc_replacement);
+
+        let acc_arms = &self.acc_arms;
+        self.acc_arms = quote! {
+            #acc_arms
+
+            #cfg_attrs
+            (Self::#ident { #self_pattern }, #orig_name::#ident { #base_pattern .. }) => {
+                #acc_concrete
+            }
+        };
+
+        let acc_replacement_arms = &self.acc_replacement_arms;
+        self.acc_replacement_arms = quote! {
+            #acc_replacement_arms
+
+            #cfg_attrs
+            Self::#ident { #self_pattern } => #orig_name::#ident { #replacement },
+        };
+        self.variant_count += 1;
+    }
+}
+
+/// The generics of `orig` bounded by `bounds`, which may not hold even when
+/// they do not mention a generic parameter: target enums are not required to
+/// implement `FromBase` or `Mergeable`. The otherwise unused lifetime keeps
+/// such bounds from being rejected, in which case the implementation simply
+/// does not exist.
+fn with_optional_bounds(
+    orig: &DeriveInput,
+    bounds: &[TokenStream],
+) -> syn::Generics {
+    let mut generics = orig.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .extend(bounds.iter().map(|b| -> syn::WherePredicate {
+            syn::parse_quote! { for<'__enumify> #b }
+        }));
+    generics
+}
+
+/// The bounds of an implementation that may not hold, as with
+/// `with_optional_bounds`, including those of conditionally compiled fields.
+#[derive(Default)]
+struct OptionalBounds {
+    bounds: Vec<TokenStream>,
+    /// The bounds of each conditionally compiled field, along with its `cfg`
+    /// attribute.
+    cfg_bounds: Vec<(Attribute, Vec<TokenStream>)>,
+}
+
+impl OptionalBounds {
+    /// Adds `bound`, which only applies when `cfg` holds.
+    fn push(&mut self, cfg: Option<&Attribute>, bound: TokenStream) {
+        let Some(cfg) = cfg else {
+            self.bounds.push(bound);
+            return;
+        };
+        // Consecutive bounds with the same `cfg` share a macro
+        let cfg_string = cfg.to_token_stream().to_string();
+        match self.cfg_bounds.last_mut() {
+            Some((last, bounds))
+                if last.to_token_stream().to_string() == cfg_string =>
+            {
+                bounds.push(bound)
+            }
+            _ => self.cfg_bounds.push((cfg.clone(), vec![bound])),
+        }
+    }
+
+    /// The implementation of `orig` whose header is `head`, e.g. `impl<T>
+    /// Trait for Type<T>`, and whose items are within `body`. Attributes on
+    /// where predicates are unstable, so the bounds of conditionally compiled
+    /// fields are added by a macro, which only sees the `cfg` that holds.
+    fn implement(
+        self,
+        orig: &DeriveInput,
+        head: TokenStream,
+        body: TokenStream,
+    ) -> TokenStream {
+        let mut generics = with_optional_bounds(orig, &self.bounds);
+        let where_clause = generics.make_where_clause();
+        if self.cfg_bounds.is_empty() {
+            return quote! { #head #where_clause #body };
+        }
+        if !where_clause.predicates.empty_or_trailing() {
+            where_clause.predicates.push_punct(Comma::default());
+        }
+
+        let cfg_bounds = self.cfg_bounds.iter().map(|(cfg, bounds)| {
+            quote! { #cfg [#(for<'__enumify> #bounds,)*] }
+        });
+        quote! {
+            enumify_struct::__private::cfg_bounds! {
+                { #head #where_clause } [] #body
+                #(#cfg_bounds)*
+            }
+        }
+    }
+}
+
+/// The type and const parameters of `orig`.
+fn generic_params(orig: &DeriveInput) -> Vec<Ident> {
+    let type_params = orig.generics.type_params().map(|p| p.ident.clone());
+    let const_params = orig.generics.const_params().map(|p| p.ident.clone());
+    type_params.chain(const_params).collect()
+}
+
+/// Whether `tokens` mention one of `generic_params`.
+fn is_generic(generic_params: &[Ident], tokens: TokenStream) -> bool {
+    tokens.into_iter().any(|tt| match tt {
+        TokenTree::Ident(ident) => generic_params.contains(&ident),
+        TokenTree::Group(group) => is_generic(generic_params, group.stream()),
+        _ => false,
+    })
+}
+
+/// The generics of `orig` bounded by `bounds`, which are reported where they
+/// do not hold.
+fn with_bounds(orig: &DeriveInput, bounds: &[TokenStream]) -> syn::Generics {
+    let mut generics = orig.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .extend(bounds.iter().map(|b| -> syn::WherePredicate {
+            syn::parse_quote! { #b }
+        }));
+    generics
+}
+
+/// Generates `From<Base>` for the generated structure, wrapping every field
+/// back into the target enum through `FromBase`.
+struct GenerateFromBaseImplVisitor {
+    orig_name: Ident,
+    acc_bounds: OptionalBounds,
+    acc_pattern: TokenStream,
+    acc_fields: TokenStream,
+    acc_arms: TokenStream,
+}
+
+impl GenerateFromBaseImplVisitor {
+    fn new(orig: &DeriveInput) -> Self {
+        GenerateFromBaseImplVisitor {
+            orig_name: orig.ident.clone(),
+            acc_bounds: OptionalBounds::default(),
+            acc_pattern: quote! {},
+            acc_fields: quote! {},
+            acc_arms: quote! {},
         }
     }
 
@@ -52,86 +999,777 @@ impl GenerateApplicableImplVisitor {
         let (impl_generics, ty_generics, _) = orig.generics.split_for_impl();
         let orig_name = &orig.ident;
         let new_name = &new.ident;
-        let acc_concrete = self.acc_concrete;
 
-        quote! {
-            impl #impl_generics enumify_struct::Applicable for #new_name #ty_generics {
-                type Base = #orig_name #ty_generics;
+        let body = if matches!(orig.data, Data::Enum(_)) {
+            let arms = self.acc_arms;
+            quote! {
+                match t {
+                    #arms
+                }
+            }
+        } else {
+            let fields = self.acc_fields;
+            quote! { Self { #fields } }
+        };
+
+        self.acc_bounds.implement(
+            orig,
+            quote! {
+                impl #impl_generics From<#orig_name #ty_generics> for #new_name #ty_generics
+            },
+            quote! {{
+                fn from(t: #orig_name #ty_generics) -> Self {
+                    #body
+                }
+            }},
+        )
+    }
+
+    /// The value of a field of the generated structure, converted from
+    /// `src`, the same field of the base.
+    fn convert(
+        &mut self,
+        old_field: &Field,
+        new_field: &Field,
+        field_options: &FieldOptions,
+        src: &TokenStream,
+    ) -> TokenStream {
+        let cfg_attr = field_options.cfg_attribute.as_ref();
+        let unwrapped = field_options.unwrapped_type(old_field);
+        let is_wrapped = field_options.wrapping_behavior;
+        let is_base_enum = field_options.is_base_enum;
+
+        let converted = if field_options.new_type.is_none() {
+            quote! { #src }
+        } else if is_base_enum {
+            // Only the value held by the base enum is nested
+            let base = get_wrapped_type(&old_field.ty)
+                .map_or_else(|| quote! { _ }, ToTokens::to_token_stream);
+            self.acc_bounds
+                .push(cfg_attr, quote! { #unwrapped: From<#base> });
+            let from = quote! { <#unwrapped as From<#base>>::from };
+            if is_wrapped {
+                return quote! { #src.map(#from) };
+            }
+            self.acc_bounds
+                .push(cfg_attr, quote! { #unwrapped: Default });
+            return quote! { #src.map(#from).unwrap_or_default() };
+        } else {
+            let base = &old_field.ty;
+            self.acc_bounds
+                .push(cfg_attr, quote! { #unwrapped: From<#base> });
+            quote! { <#unwrapped as From<#base>>::from(#src) }
+        };
+
+        if !is_wrapped {
+            return converted;
+        }
+        let new_ty = &new_field.ty;
+        let from_base = quote! { enumify_struct::FromBase<#unwrapped> };
+        self.acc_bounds
+            .push(cfg_attr, quote! { #new_ty: #from_base });
+        quote! { <#new_ty as #from_base>::from_base(#converted) }
+    }
+}
+
+impl EnumFieldVisitor for GenerateFromBaseImplVisitor {
+    fn visit(
+        &mut self,
+        _global_options: &GlobalOptions,
+        old_field: &mut Field,
+        new_field: &mut Field,
+        field_options: &FieldOptions,
+    ) {
+        let cfg_attr = &field_options.cfg_attribute;
+        let src = field_options.access("t", false, false);
+        let converted = self.convert(old_field, new_field, field_options, &src);
+        let ident = field_options.member("self");
+
+        let acc_fields = &self.acc_fields;
+        self.acc_fields = quote! { #acc_fields #cfg_attr #ident: #converted, };
+        if field_options.in_variant {
+            let pattern = field_options.pattern("t");
+            let acc_pattern = &self.acc_pattern;
+            self.acc_pattern = quote! { #acc_pattern #pattern, };
+        }
+    }
+
+    fn end_variant(&mut self, variant: &Variant) {
+        let orig_name = &self.orig_name;
+        let ident = &variant.ident;
+        let cfg_attrs = variant
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident(CFG_ATTRIBUTE));
+
+        let pattern = std::mem::take(&mut self.acc_pattern);
+        let fields = std::mem::take(&mut self.acc_fields);
+        let acc_arms = &self.acc_arms;
+        self.acc_arms = quote! {
+            #acc_arms
+
+            #(#cfg_attrs)*
+            #orig_name::#ident { #pattern .. } => Self::#ident { #fields },
+        };
+    }
+}
+
+/// Generates `Mergeable` for the generated structure, merging every field
+/// through the `Mergeable` implementation of the target enum.
+struct GenerateMergeImplVisitor {
+    acc_bounds: OptionalBounds,
+    acc_self_pattern: TokenStream,
+    acc_other_pattern: TokenStream,
+    acc_fields: TokenStream,
+    acc_arms: TokenStream,
+    variant_count: usize,
+}
+
+impl GenerateMergeImplVisitor {
+    fn new() -> Self {
+        GenerateMergeImplVisitor {
+            acc_bounds: OptionalBounds::default(),
+            acc_self_pattern: quote! {},
+            acc_other_pattern: quote! {},
+            acc_fields: quote! {},
+            acc_arms: quote! {},
+            variant_count: 0,
+        }
+    }
+
+    fn get_implementation(
+        self,
+        orig: &DeriveInput,
+        new: &DeriveInput,
+    ) -> TokenStream {
+        let (impl_generics, ty_generics, _) = orig.generics.split_for_impl();
+        let new_name = &new.ident;
+
+        let body = if !matches!(orig.data, Data::Enum(_)) {
+            let fields = self.acc_fields;
+            quote! { Self { #fields } }
+        } else if self.variant_count == 0 {
+            quote! { match self {} }
+        } else {
+            // Layers holding different variants cannot be merged field-wise
+            let arms = self.acc_arms;
+            let fallback = if self.variant_count > 1 {
+                quote! { (_, other) => other, }
+            } else {
+                quote! {}
+            };
+            quote! {
+                match (self, other) {
+                    #arms
+                    #fallback
+                }
+            }
+        };
+
+        self.acc_bounds.implement(
+            orig,
+            quote! {
+                impl #impl_generics enumify_struct::Mergeable for #new_name #ty_generics
+            },
+            quote! {{
+                fn merge(self, other: Self) -> Self {
+                    #body
+                }
+            }},
+        )
+    }
+}
+
+impl EnumFieldVisitor for GenerateMergeImplVisitor {
+    fn visit(
+        &mut self,
+        _global_options: &GlobalOptions,
+        _old_field: &mut Field,
+        new_field: &mut Field,
+        field_options: &FieldOptions,
+    ) {
+        let cfg_attr = &field_options.cfg_attribute;
+        let ident = field_options.member("self");
+        let src = field_options.access("self", false, false);
+        let other = field_options.access("other", false, false);
+
+        // Fields holding neither the target enum nor a nested structure are
+        // plain values, which the other layer overrides
+        let is_mergeable = field_options.wrapping_behavior
+            || field_options.is_base_enum
+            || field_options.new_type.is_some();
+        let merged = if is_mergeable {
+            let new_ty = &new_field.ty;
+            self.acc_bounds.push(
+                cfg_attr.as_ref(),
+                quote! { #new_ty: enumify_struct::Mergeable },
+            );
+            quote! {
+                <#new_ty as enumify_struct::Mergeable>::merge(#src, #other)
+            }
+        } else {
+            other
+        };
+
+        let acc_fields = &self.acc_fields;
+        self.acc_fields = quote! { #acc_fields #cfg_attr #ident: #merged, };
+        if field_options.in_variant {
+            let self_pattern = field_options.pattern("self");
+            let other_pattern = field_options.pattern("other");
+            let acc_self_pattern = &self.acc_self_pattern;
+            let acc_other_pattern = &self.acc_other_pattern;
+            self.acc_self_pattern = quote! { #acc_self_pattern #self_pattern, };
+            self.acc_other_pattern =
+                quote! { #acc_other_pattern #other_pattern, };
+        }
+    }
+
+    fn end_variant(&mut self, variant: &Variant) {
+        let ident = &variant.ident;
+        let cfg_attrs = variant
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident(CFG_ATTRIBUTE));
+
+        let self_pattern = std::mem::take(&mut self.acc_self_pattern);
+        let other_pattern = std::mem::take(&mut self.acc_other_pattern);
+        let fields = std::mem::take(&mut self.acc_fields);
+        let acc_arms = &self.acc_arms;
+        self.acc_arms = quote! {
+            #acc_arms
+
+            #(#cfg_attrs)*
+            (Self::#ident { #self_pattern }, Self::#ident { #other_pattern }) => {
+                Self::#ident { #fields }
+            }
+        };
+        self.variant_count += 1;
+    }
+}
+
+/// Generates `FromEnv` for the generated structs annotated with
+/// `enumify_from_env`, reading wrapped fields from the variable named after
+/// them and nested structures from the variables under their own prefix,
+/// wrapped nested structures being unset when none of them is. Fields that
+/// are neither wrapped nor nested are set by every layer, as are those
+/// already holding the target enum, so their variable is required.
+struct GenerateFromEnvImplVisitor {
+    is_enabled: bool,
+    generic_params: Vec<Ident>,
+    acc_bounds: Vec<TokenStream>,
+    acc_fields: TokenStream,
+    errors: ErrorAccumulator,
+}
+
+impl GenerateFromEnvImplVisitor {
+    fn new(orig: &DeriveInput, is_enabled: bool) -> Self {
+        GenerateFromEnvImplVisitor {
+            is_enabled,
+            generic_params: generic_params(orig),
+            acc_bounds: Vec::new(),
+            acc_fields: quote! {},
+            errors: ErrorAccumulator::default(),
+        }
+    }
+
+    fn get_implementation(
+        self,
+        orig: &DeriveInput,
+        new: &DeriveInput,
+    ) -> syn::Result<TokenStream> {
+        if !self.is_enabled {
+            return Ok(quote! {});
+        }
+        let generics = with_bounds(orig, &self.acc_bounds);
+        let (impl_generics, ty_generics, where_clause) =
+            generics.split_for_impl();
+        let new_name = &new.ident;
+        let fields = self.acc_fields;
+
+        self.errors.finish(quote! {
+            impl #impl_generics enumify_struct::FromEnv for #new_name #ty_generics #where_clause {
+                fn from_env_with(
+                    prefix: &str,
+                    var: &dyn Fn(&str) -> Option<String>,
+                ) -> Result<Self, enumify_struct::ResolveError> {
+                    Ok(Self { #fields })
+                }
+            }
+        })
+    }
+}
+
+/// The bounds parsing a field of type `ty` from a string requires.
+fn from_str_bounds(ty: &TokenStream) -> [TokenStream; 2] {
+    [
+        quote! { #ty: std::str::FromStr },
+        quote! {
+            <#ty as std::str::FromStr>::Err: Into<
+                Box<dyn std::error::Error + Send + Sync>
+            >
+        },
+    ]
+}
+
+impl EnumFieldVisitor for GenerateFromEnvImplVisitor {
+    fn visit(
+        &mut self,
+        _global_options: &GlobalOptions,
+        old_field: &mut Field,
+        new_field: &mut Field,
+        field_options: &FieldOptions,
+    ) {
+        if !self.is_enabled {
+            return;
+        }
+        let cfg_attr = &field_options.cfg_attribute;
+        let new_ty = &new_field.ty;
+        // Bounds cannot be conditionally compiled, and those on concrete
+        // types are checked where the field is read instead
+        let is_bounded = cfg_attr.is_none()
+            && is_generic(&self.generic_params, new_ty.to_token_stream());
+        let span = old_field.ty.span();
+        let ident = field_options.member("self");
+        let unwrapped = field_options.unwrapped_type(old_field);
+        let name = field_options.name().to_uppercase();
+        let var_name = quote! {
+            &enumify_struct::__private::env_var_name(prefix, #name)
+        };
+
+        let is_nested = field_options.new_type.is_some();
+        let from_base = quote! { enumify_struct::FromBase<#unwrapped> };
+        let value = if field_options.skip_parse {
+            if is_bounded {
+                self.acc_bounds.push(quote! { #new_ty: Default });
+            }
+            quote! { <#new_ty as Default>::default() }
+        } else if field_options.is_plain() {
+            if is_bounded {
+                self.acc_bounds.extend(from_str_bounds(&unwrapped));
+            }
+            quote_spanned! {span=>
+                enumify_struct::__private::parse_required_env_var::<#unwrapped>(
+                    var,
+                    #var_name
+                )?
+            }
+        } else if field_options.is_base_enum
+            && !field_options.wrapping_behavior
+            && !is_nested
+        {
+            let Some(wrapped) = get_wrapped_type(&old_field.ty) else {
+                self.errors.push(syn::Error::new_spanned(
+                    &old_field.ty,
+                    format!("'{FROM_ENV_ATTRIBUTE}' expects the target enum to wrap the type to parse"),
+                ));
+                return;
+            };
+            let from_base = quote! { enumify_struct::FromBase<#wrapped> };
+            if is_bounded {
+                self.acc_bounds.push(quote! { #new_ty: #from_base });
+                self.acc_bounds
+                    .extend(from_str_bounds(&quote! { #wrapped }));
+            }
+            quote_spanned! {span=>
+                <#new_ty as #from_base>::from_base(
+                    enumify_struct::__private::parse_required_env_var::<#wrapped>(
+                        var,
+                        #var_name
+                    )?
+                )
+            }
+        } else if field_options.wrapping_behavior && !is_nested {
+            if is_bounded {
+                self.acc_bounds.extend([
+                    quote! { #new_ty: #from_base },
+                    quote! { #new_ty: Default },
+                ]);
+                self.acc_bounds.extend(from_str_bounds(&unwrapped));
+            }
+            quote_spanned! {span=>
+                match enumify_struct::__private::parse_env_var::<#unwrapped>(
+                    var,
+                    #var_name,
+                )? {
+                    Some(value) => <#new_ty as #from_base>::from_base(value),
+                    None => <#new_ty as Default>::default(),
+                }
+            }
+        } else if field_options.wrapping_behavior {
+            if is_bounded {
+                self.acc_bounds.extend([
+                    quote! { #new_ty: #from_base },
+                    quote! { #new_ty: Default },
+                    quote! { #unwrapped: enumify_struct::FromEnv },
+                ]);
+            }
+            quote_spanned! {span=>
+                match enumify_struct::__private::read_nested_env::<#unwrapped>(
+                    &enumify_struct::__private::nested_env_prefix(prefix, #name),
+                    var,
+                )? {
+                    Some(value) => <#new_ty as #from_base>::from_base(value),
+                    None => <#new_ty as Default>::default(),
+                }
+            }
+        } else {
+            if is_bounded {
+                self.acc_bounds
+                    .push(quote! { #new_ty: enumify_struct::FromEnv });
+            }
+            quote_spanned! {span=>
+                <#new_ty as enumify_struct::FromEnv>::from_env_with(
+                    &enumify_struct::__private::nested_env_prefix(prefix, #name),
+                    var,
+                )?
+            }
+        };
+
+        let acc_fields = &self.acc_fields;
+        self.acc_fields = quote! { #acc_fields #cfg_attr #ident: #value, };
+    }
+}
+
+/// Generates `clap::Args` for the generated structs annotated with
+/// `enumify_clap`, through `PrefixedArgs` so that the flags of nested
+/// structures are prefixed with the field they are nested in. Wrapped fields
+/// become optional flags named after them, whose help is their documentation,
+/// and wrapped nested structures are unset when none of their flags is given.
+/// Fields that are neither wrapped nor nested are set by every layer, as are
+/// those already holding the target enum, so their flag is required unless
+/// it only updates an existing layer.
+struct GenerateClapImplVisitor {
+    is_enabled: bool,
+    generic_params: Vec<Ident>,
+    acc_bounds: Vec<TokenStream>,
+    acc_args: TokenStream,
+    acc_fields: TokenStream,
+    acc_updates: TokenStream,
+    errors: ErrorAccumulator,
+}
+
+impl GenerateClapImplVisitor {
+    fn new(orig: &DeriveInput, is_enabled: bool) -> Self {
+        GenerateClapImplVisitor {
+            is_enabled,
+            generic_params: generic_params(orig),
+            acc_bounds: Vec::new(),
+            acc_args: quote! {},
+            acc_fields: quote! {},
+            acc_updates: quote! {},
+            errors: ErrorAccumulator::default(),
+        }
+    }
+
+    fn get_implementation(
+        self,
+        orig: &DeriveInput,
+        new: &DeriveInput,
+    ) -> syn::Result<TokenStream> {
+        if !self.is_enabled {
+            return Ok(quote! {});
+        }
+        let generics = with_bounds(orig, &self.acc_bounds);
+        let (impl_generics, ty_generics, where_clause) =
+            generics.split_for_impl();
+        let new_name = &new.ident;
+        let args = self.acc_args;
+        let fields = self.acc_fields;
+        let updates = self.acc_updates;
+
+        let prefixed_args = quote! { enumify_struct::__private::PrefixedArgs };
+        let clap = quote! { enumify_struct::__private::clap };
 
-                fn apply_to(self, t: &mut Self::Base) {
-                    #acc_concrete
+        self.errors.finish(quote! {
+            impl #impl_generics #prefixed_args for #new_name #ty_generics #where_clause {
+                fn augment_args_prefixed(
+                    cmd: #clap::Command,
+                    prefix: &str,
+                    for_update: bool,
+                ) -> #clap::Command {
+                    #args
+                    cmd
                 }
-            }
-        }
-    }
 
-    fn get_incremental_setter_concrete(
-        ident: &TokenStream,
-        is_wrapped: bool,
-        is_nested: bool,
-        is_base_enum: bool,
-    ) -> TokenStream {
-        match (is_base_enum, is_wrapped, is_nested) {
-            (true, false, true) => quote! {
-                if let Some(existing) = &mut t.#ident {
-                    self.#ident.apply_to(existing);
-                } else {
-                    t.#ident = self.#ident.try_into().ok();
+                fn from_arg_matches_prefixed(
+                    matches: &#clap::ArgMatches,
+                    prefix: &str,
+                ) -> Result<Self, #clap::Error> {
+                    Ok(Self { #fields })
+                }
+
+                fn update_from_arg_matches_prefixed(
+                    &mut self,
+                    matches: &#clap::ArgMatches,
+                    prefix: &str,
+                ) -> Result<(), #clap::Error> {
+                    #updates
+                    Ok(())
                 }
-            },
-            (true, false, false) => quote! {
-                t.#ident = self.#ident;
-            },
-            (false, false, true) => {
-                quote! { self.#ident.apply_to(&mut t.#ident); }
-            }
-            (false, false, false) => quote! { t.#ident = self.#ident; },
-            (true, true, true) => {
-                quote! { if let (Some(inner), Some(target)) = (self.#ident, &mut t.#ident) { inner.apply_to(target); } }
             }
-            (false, true, true) => {
-                quote! {
-                    let inner = self.#ident.resolve_to_base();
-                    inner.apply_to(&mut t.#ident);
+
+            impl #impl_generics #clap::FromArgMatches for #new_name #ty_generics #where_clause {
+                fn from_arg_matches(
+                    matches: &#clap::ArgMatches,
+                ) -> Result<Self, #clap::Error> {
+                    <Self as #prefixed_args>::from_arg_matches_prefixed(matches, "")
+                }
+
+                fn update_from_arg_matches(
+                    &mut self,
+                    matches: &#clap::ArgMatches,
+                ) -> Result<(), #clap::Error> {
+                    <Self as #prefixed_args>::update_from_arg_matches_prefixed(
+                        self, matches, ""
+                    )
                 }
             }
-            (_, true, false) => {
-                quote! { t.#ident = self.#ident.resolve_to_base(); }
+
+            impl #impl_generics #clap::Args for #new_name #ty_generics #where_clause {
+                fn augment_args(cmd: #clap::Command) -> #clap::Command {
+                    <Self as #prefixed_args>::augment_args_prefixed(cmd, "", false)
+                }
+
+                fn augment_args_for_update(
+                    cmd: #clap::Command,
+                ) -> #clap::Command {
+                    <Self as #prefixed_args>::augment_args_prefixed(cmd, "", true)
+                }
             }
-        }
+        })
     }
 }
 
-impl EnumFieldVisitor for GenerateApplicableImplVisitor {
+/// The bounds parsing a flag of type `ty` requires on top of parsing it from
+/// a string.
+fn arg_bounds(ty: &TokenStream) -> [TokenStream; 3] {
+    let [from_str, error] = from_str_bounds(ty);
+    [
+        from_str,
+        error,
+        quote! { #ty: Clone + Send + Sync + 'static },
+    ]
+}
+
+/// The documentation of `field`, joined into a single line.
+fn get_doc(field: &Field) -> String {
+    field
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(doc),
+                        ..
+                    }),
+                ..
+            }) => Some(doc.value().trim().to_owned()),
+            _ => None,
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl EnumFieldVisitor for GenerateClapImplVisitor {
     fn visit(
         &mut self,
-        global_options: &GlobalOptions,
+        _global_options: &GlobalOptions,
         old_field: &mut Field,
-        _new_field: &mut Field,
+        new_field: &mut Field,
         field_options: &FieldOptions,
     ) {
-        let ident = &field_options.field_ident;
+        if !self.is_enabled {
+            return;
+        }
         let cfg_attr = &field_options.cfg_attribute;
+        let new_ty = &new_field.ty;
+        // Bounds cannot be conditionally compiled, and those on concrete
+        // types are checked where the field is read instead
+        let is_bounded = cfg_attr.is_none()
+            && is_generic(&self.generic_params, new_ty.to_token_stream());
+        let span = old_field.ty.span();
+        let ident = field_options.member("self");
+        let unwrapped = field_options.unwrapped_type(old_field);
+        let name = field_options.name().replace('_', "-");
+        let help = get_doc(old_field);
+        let get_arg = quote_spanned! {span=>
+            enumify_struct::__private::get_arg::<#unwrapped>(
+                matches, prefix, #name
+            )
+        };
 
-        let is_wrapped = field_options.wrapping_behavior;
         let is_nested = field_options.new_type.is_some();
-        let is_base_enum =
-            is_type_target_enum(&old_field.ty, &global_options.target_enum);
-
-        let inc_concrete = Self::get_incremental_setter_concrete(
-            ident,
-            is_wrapped,
-            is_nested,
-            is_base_enum,
-        );
-
-        let acc_concrete = &self.acc_concrete;
-        self.acc_concrete = quote! {
-            #acc_concrete
-
-            #cfg_attr
-            #inc_concrete
+        let from_base = quote! { enumify_struct::FromBase<#unwrapped> };
+        let prefixed_args = quote! { enumify_struct::__private::PrefixedArgs };
+        let prefix = quote! {
+            &enumify_struct::__private::nested_arg_prefix(prefix, #name)
+        };
+        let (arg, value, update) = if field_options.skip_parse {
+            if is_bounded {
+                self.acc_bounds.push(quote! { #new_ty: Default });
+            }
+            (
+                quote! {},
+                quote! { <#new_ty as Default>::default() },
+                quote! {},
+            )
+        } else if field_options.is_plain() {
+            if is_bounded {
+                self.acc_bounds.extend(arg_bounds(&unwrapped));
+            }
+            (
+                quote_spanned! {span=>
+                    let cmd = cmd.arg(
+                        enumify_struct::__private::arg::<#unwrapped>(
+                            prefix, #name, #help
+                        )
+                        .required(!for_update)
+                    );
+                },
+                quote_spanned! {span=>
+                    enumify_struct::__private::get_required_arg::<#unwrapped>(
+                        matches, prefix, #name
+                    )?
+                },
+                quote_spanned! {span=>
+                    if let Some(value) = #get_arg {
+                        self.#ident = value;
+                    }
+                },
+            )
+        } else if field_options.is_base_enum
+            && !field_options.wrapping_behavior
+            && !is_nested
+        {
+            let Some(wrapped) = get_wrapped_type(&old_field.ty) else {
+                self.errors.push(syn::Error::new_spanned(
+                    &old_field.ty,
+                    format!("'{CLAP_ATTRIBUTE}' expects the target enum to wrap the type to parse"),
+                ));
+                return;
+            };
+            let from_base = quote! { enumify_struct::FromBase<#wrapped> };
+            if is_bounded {
+                self.acc_bounds.push(quote! { #new_ty: #from_base });
+                self.acc_bounds.extend(arg_bounds(&quote! { #wrapped }));
+            }
+            (
+                quote_spanned! {span=>
+                    let cmd = cmd.arg(
+                        enumify_struct::__private::arg::<#wrapped>(
+                            prefix, #name, #help
+                        )
+                        .required(!for_update)
+                    );
+                },
+                quote_spanned! {span=>
+                    <#new_ty as #from_base>::from_base(
+                        enumify_struct::__private::get_required_arg::<#wrapped>(
+                            matches, prefix, #name
+                        )?
+                    )
+                },
+                quote_spanned! {span=>
+                    if let Some(value) = enumify_struct::__private::get_arg::<#wrapped>(
+                        matches, prefix, #name
+                    ) {
+                        self.#ident = <#new_ty as #from_base>::from_base(value);
+                    }
+                },
+            )
+        } else if field_options.wrapping_behavior && !is_nested {
+            if is_bounded {
+                self.acc_bounds.extend([
+                    quote! { #new_ty: #from_base },
+                    quote! { #new_ty: Default },
+                ]);
+                self.acc_bounds.extend(arg_bounds(&unwrapped));
+            }
+            (
+                quote_spanned! {span=>
+                    let cmd = cmd.arg(
+                        enumify_struct::__private::arg::<#unwrapped>(
+                            prefix, #name, #help
+                        )
+                    );
+                },
+                quote_spanned! {span=>
+                    match #get_arg {
+                        Some(value) => <#new_ty as #from_base>::from_base(value),
+                        None => <#new_ty as Default>::default(),
+                    }
+                },
+                quote_spanned! {span=>
+                    if let Some(value) = #get_arg {
+                        self.#ident = <#new_ty as #from_base>::from_base(value);
+                    }
+                },
+            )
+        } else if field_options.wrapping_behavior {
+            if is_bounded {
+                self.acc_bounds.extend([
+                    quote! { #new_ty: #from_base },
+                    quote! {
+                        #new_ty: enumify_struct::OptionalIntoBase<#unwrapped>
+                    },
+                    quote! { #new_ty: Default },
+                    quote! { #unwrapped: #prefixed_args },
+                ]);
+            }
+            // The flags of a structure left unset are optional, those of the
+            // fields it requires being checked once one of them is given
+            (
+                quote_spanned! {span=>
+                    let cmd = <#unwrapped as #prefixed_args>::augment_args_prefixed(
+                        cmd, #prefix, true
+                    );
+                },
+                quote_spanned! {span=>
+                    match enumify_struct::__private::get_nested_args::<#unwrapped>(
+                        matches, #prefix
+                    )? {
+                        Some(value) => <#new_ty as #from_base>::from_base(value),
+                        None => <#new_ty as Default>::default(),
+                    }
+                },
+                quote_spanned! {span=>
+                    enumify_struct::__private::update_nested_args::<#unwrapped, _>(
+                        &mut self.#ident, matches, #prefix
+                    )?;
+                },
+            )
+        } else {
+            if is_bounded {
+                self.acc_bounds.push(quote! { #new_ty: #prefixed_args });
+            }
+            (
+                quote_spanned! {span=>
+                    let cmd = <#new_ty as #prefixed_args>::augment_args_prefixed(
+                        cmd, #prefix, for_update
+                    );
+                },
+                quote_spanned! {span=>
+                    <#new_ty as #prefixed_args>::from_arg_matches_prefixed(
+                        matches, #prefix
+                    )?
+                },
+                quote_spanned! {span=>
+                    <#new_ty as #prefixed_args>::update_from_arg_matches_prefixed(
+                        &mut self.#ident, matches, #prefix
+                    )?;
+                },
+            )
         };
+
+        let acc_args = &self.acc_args;
+        self.acc_args = quote! { #acc_args #cfg_attr #arg };
+        let acc_fields = &self.acc_fields;
+        self.acc_fields = quote! { #acc_fields #cfg_attr #ident: #value, };
+        let acc_updates = &self.acc_updates;
+        self.acc_updates = quote! { #acc_updates #cfg_attr { #update } };
     }
 }
 
@@ -143,9 +1781,10 @@ impl EnumFieldVisitor for SetNewFieldVisibilityVisitor {
         global_options: &GlobalOptions,
         _old_field: &mut Field,
         new_field: &mut Field,
-        _field_options: &FieldOptions,
+        field_options: &FieldOptions,
     ) {
-        if global_options.make_fields_public {
+        // Enum variant fields cannot have a visibility
+        if global_options.make_fields_public && !field_options.in_variant {
             new_field.vis =
                 Visibility::Public(syn::token::Pub(new_field.vis.span()))
         }
@@ -162,12 +1801,7 @@ impl EnumFieldVisitor for SetNewFieldTypeVisitor {
         new_field: &mut Field,
         field_options: &FieldOptions,
     ) {
-        let mut new_type = if let Some(t) = &field_options.new_type {
-            quote! {#t}
-        } else {
-            let t = &old_field.ty;
-            quote! {#t}
-        };
+        let mut new_type = field_options.unwrapped_type(old_field);
         let target_enum = &global_options.target_enum;
         if field_options.wrapping_behavior {
             new_type = quote! {#target_enum<#new_type>};
@@ -176,31 +1810,32 @@ impl EnumFieldVisitor for SetNewFieldTypeVisitor {
     }
 }
 
+fn is_helper_attribute(a: &Attribute) -> bool {
+    a.path().is_ident(RENAME_ATTRIBUTE)
+        || a.path().is_ident(NESTED_ATTRIBUTE)
+        || a.path().is_ident(SKIP_PARSE_ATTRIBUTE)
+        || a.path().is_ident(SKIP_ATTRIBUTE)
+        || a.path().is_ident(SKIP_WRAP_ATTRIBUTE)
+        || a.path().is_ident(WRAP_ATTRIBUTE)
+        || a.path().is_ident(FORWARD_ATTRIBUTE)
+}
+
 // https://github.com/rust-lang/rust/issues/65823 :(
 struct RemoveHelperAttributesVisitor;
 
 impl EnumFieldVisitor for RemoveHelperAttributesVisitor {
     fn visit(
         &mut self,
-        _global_options: &GlobalOptions,
+        global_options: &GlobalOptions,
         old_field: &mut Field,
         new_field: &mut Field,
-        _field_options: &FieldOptions,
+        field_options: &FieldOptions,
     ) {
         let indexes_to_remove = old_field
             .attrs
             .iter()
             .enumerate()
-            .filter_map(|(i, a)| {
-                if a.path().is_ident(RENAME_ATTRIBUTE)
-                    || a.path().is_ident(SKIP_WRAP_ATTRIBUTE)
-                    || a.path().is_ident(WRAP_ATTRIBUTE)
-                {
-                    Some(i)
-                } else {
-                    None
-                }
-            })
+            .filter_map(|(i, a)| is_helper_attribute(a).then_some(i))
             .collect::<Vec<_>>();
 
         // Don't forget to reverse so the indices are removed without being
@@ -209,149 +1844,779 @@ impl EnumFieldVisitor for RemoveHelperAttributesVisitor {
             old_field.attrs.swap_remove(i);
             new_field.attrs.swap_remove(i);
         }
+
+        retain_serde_attributes(
+            &mut new_field.attrs,
+            global_options.derives_serde,
+        );
+        // Missing fields deserialize to the `Default` value of the target
+        // enum, i.e. leave the base untouched
+        if global_options.derives_serde {
+            let is_target_enum =
+                field_options.wrapping_behavior || field_options.is_base_enum;
+            if is_target_enum && field_options.new_type.is_none() {
+                new_field
+                    .attrs
+                    .push(syn::parse_quote! { #[serde(default)] });
+            }
+        }
+
+        new_field
+            .attrs
+            .extend(field_options.forwarded_attributes.iter().cloned());
     }
 }
 
-fn borrow_fields(
-    derive_input: &mut DeriveInput,
-) -> &mut Punctuated<Field, Comma> {
-    let data_struct = match &mut derive_input.data {
-        Data::Struct(data_struct) => data_struct,
-        _ => panic!("enumify_struct only works for structs"),
-    };
+/// The `serde` attributes that still apply to the generated structure, which
+/// mostly holds other types than its base: they only change names and which
+/// fields are (de)serialized.
+const SERDE_RENAMES: &[&str] = &[
+    "rename",
+    "rename_all",
+    "rename_all_fields",
+    "alias",
+    "skip",
+    "skip_serializing",
+    "skip_deserializing",
+    "tag",
+    "content",
+    "untagged",
+    "deny_unknown_fields",
+];
+
+/// Removes the `serde` attributes copied from the original item when the
+/// generated one does not derive the `serde` traits, which would leave them
+/// unknown, and otherwise only keeps their renames.
+fn retain_serde_attributes(attrs: &mut Vec<Attribute>, derives_serde: bool) {
+    if derives_serde {
+        retain_serde_renames(attrs);
+    } else {
+        attrs.retain(|a| !a.path().is_ident("serde"));
+    }
+}
+
+/// Only keeps the parts of the `serde` attributes copied from the original
+/// item listed in `SERDE_RENAMES`, as e.g. `with` or `default` functions
+/// expect its types.
+fn retain_serde_renames(attrs: &mut Vec<Attribute>) {
+    let mut retained = Vec::with_capacity(attrs.len());
+    for a in std::mem::take(attrs) {
+        if !a.path().is_ident("serde") {
+            retained.push(a);
+            continue;
+        }
+        let Ok(metas) = a.parse_args_with(
+            Punctuated::<syn::Meta, Token![,]>::parse_terminated,
+        ) else {
+            continue;
+        };
+        let renames = metas
+            .into_iter()
+            .filter(|m| SERDE_RENAMES.iter().any(|r| m.path().is_ident(r)))
+            .collect::<Vec<_>>();
+        if !renames.is_empty() {
+            retained.push(syn::parse_quote! { #[serde(#(#renames),*)] });
+        }
+    }
+    *attrs = retained;
+}
+
+/// Accumulates errors so that every problem in the input is reported at once
+/// instead of stopping at the first one.
+#[derive(Default)]
+struct ErrorAccumulator {
+    error: Option<syn::Error>,
+}
+
+impl ErrorAccumulator {
+    fn push(&mut self, error: syn::Error) {
+        match &mut self.error {
+            Some(existing) => existing.combine(error),
+            None => self.error = Some(error),
+        }
+    }
 
-    match &mut data_struct.fields {
-        Fields::Unnamed(f) => &mut f.unnamed,
-        Fields::Named(f) => &mut f.named,
-        Fields::Unit => {
-            unreachable!("A struct cannot have simply a unit field?")
+    fn handle<T>(&mut self, result: syn::Result<T>) -> Option<T> {
+        result.map_err(|e| self.push(e)).ok()
+    }
+
+    fn finish<T>(self, value: T) -> syn::Result<T> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(value),
         }
     }
 }
 
+fn borrow_fields(fields: &mut Fields) -> Option<&mut Punctuated<Field, Comma>> {
+    match fields {
+        Fields::Unnamed(f) => Some(&mut f.unnamed),
+        Fields::Named(f) => Some(&mut f.named),
+        Fields::Unit => None,
+    }
+}
+
 fn visit_fields(
     visitors: &mut [&mut dyn EnumFieldVisitor],
     global_options: &GlobalOptions,
     derive_input: &DeriveInput,
-) -> (DeriveInput, DeriveInput) {
+) -> syn::Result<(DeriveInput, DeriveInput)> {
     let mut new = derive_input.clone();
     let mut orig = derive_input.clone();
-    let old_fields = borrow_fields(&mut orig);
-    let new_fields = borrow_fields(&mut new);
 
-    for (struct_index, (old_field, new_field)) in
-        old_fields.iter_mut().zip(new_fields.iter_mut()).enumerate()
-    {
+    let mut errors = ErrorAccumulator::default();
+    match (&mut orig.data, &mut new.data) {
+        (Data::Struct(old_struct), Data::Struct(new_struct)) => {
+            // Unit structs have nothing to apply, and get a generated unit
+            // structure with no-op implementations
+            if let (Some(old_fields), Some(new_fields)) = (
+                borrow_fields(&mut old_struct.fields),
+                borrow_fields(&mut new_struct.fields),
+            ) {
+                visit_field_list(
+                    visitors,
+                    global_options,
+                    &mut errors,
+                    false,
+                    old_fields,
+                    new_fields,
+                );
+            }
+        }
+        (Data::Enum(old_enum), Data::Enum(new_enum)) => {
+            for (old_variant, new_variant) in old_enum
+                .variants
+                .iter_mut()
+                .zip(new_enum.variants.iter_mut())
+            {
+                if let Some(forwarded) =
+                    errors.handle(get_forwarded_attributes(&old_variant.attrs))
+                {
+                    remove_forwarding_attributes(&mut old_variant.attrs);
+                    remove_forwarding_attributes(&mut new_variant.attrs);
+                    retain_serde_attributes(
+                        &mut new_variant.attrs,
+                        global_options.derives_serde,
+                    );
+                    new_variant.attrs.extend(forwarded);
+                }
+                if let (Some(old_fields), Some(new_fields)) = (
+                    borrow_fields(&mut old_variant.fields),
+                    borrow_fields(&mut new_variant.fields),
+                ) {
+                    visit_field_list(
+                        visitors,
+                        global_options,
+                        &mut errors,
+                        true,
+                        old_fields,
+                        new_fields,
+                    );
+                }
+                for v in &mut *visitors {
+                    v.end_variant(old_variant);
+                }
+            }
+        }
+        (Data::Union(data_union), _) => {
+            return Err(syn::Error::new(
+                data_union.union_token.span(),
+                "enumify_struct only works for structs and enums",
+            ));
+        }
+        _ => unreachable!("both inputs are clones of the same item"),
+    }
+    errors.finish((orig, new))
+}
+
+fn visit_field_list(
+    visitors: &mut [&mut dyn EnumFieldVisitor],
+    global_options: &GlobalOptions,
+    errors: &mut ErrorAccumulator,
+    in_variant: bool,
+    old_fields: &mut Punctuated<Field, Comma>,
+    new_fields: &mut Punctuated<Field, Comma>,
+) {
+    let is_skipped = old_fields
+        .iter()
+        .map(|f| f.attrs.iter().any(|a| a.path().is_ident(SKIP_ATTRIBUTE)))
+        .collect::<Vec<_>>();
+    *new_fields = std::mem::take(new_fields)
+        .into_pairs()
+        .zip(&is_skipped)
+        .filter_map(|(pair, is_skipped)| (!is_skipped).then_some(pair))
+        .collect();
+
+    let mut new_fields = new_fields.iter_mut();
+    let mut new_index = 0;
+    for (struct_index, old_field) in old_fields.iter_mut().enumerate() {
+        let field_ident = if let Some(ident) = &old_field.ident {
+            Member::Named(ident.clone())
+        } else {
+            Member::Unnamed(syn::Index::from(struct_index))
+        };
+        let cfg_attribute = old_field
+            .attrs
+            .iter()
+            .rfind(|a| a.path().is_ident(CFG_ATTRIBUTE))
+            .cloned();
+
+        if is_skipped[struct_index] {
+            // The type of skipped fields does not matter, as they are
+            // never resolved
+            old_field.attrs.retain(|a| !is_helper_attribute(a));
+            let field_options = FieldOptions {
+                wrapping_behavior: false,
+                is_base_enum: false,
+                in_variant,
+                cfg_attribute,
+                new_type: None,
+                new_field_ident: field_ident.clone(),
+                field_ident,
+                forwarded_attributes: Vec::new(),
+                skip_parse: false,
+            };
+            for v in &mut *visitors {
+                v.visit_skipped(global_options, old_field, &field_options);
+            }
+            continue;
+        }
+
+        let new_field = new_fields
+            .next()
+            .expect("fields are only removed when skipped");
+        let new_field_ident = match &field_ident {
+            Member::Named(ident) => Member::Named(ident.clone()),
+            Member::Unnamed(_) => Member::Unnamed(syn::Index::from(new_index)),
+        };
+        new_index += 1;
+
+        let is_base_enum = errors.handle(is_type_target_enum(
+            &old_field.ty,
+            &global_options.target_enum,
+        ));
         let mut overriden_wrapping = false;
-        let mut wrapping_behavior =
-            !is_type_target_enum(&old_field.ty, &global_options.target_enum)
-                && global_options.default_wrapping_behavior;
-        let mut cfg_attribute = None;
+        let mut wrapping_behavior = is_base_enum == Some(false)
+            && global_options.default_wrapping_behavior;
         let mut new_type = None;
-        old_field.attrs
-            .iter()
-            .for_each(|a| {
-                if a.path().is_ident(RENAME_ATTRIBUTE) {
-                    let args = a
-                        .parse_args()
-                        .unwrap_or_else(|_| panic!("'{RENAME_ATTRIBUTE}' attribute expects one and only one argument (the new type to use)"));
-                    new_type = Some(args);
-                    if !overriden_wrapping {
-                        wrapping_behavior = false;
-                    }
-                } else if a.path().is_ident(SKIP_WRAP_ATTRIBUTE) {
+        let mut nested_attribute = None;
+        let mut skip_parse_attribute = None;
+        for a in &old_field.attrs {
+            if a.path().is_ident(RENAME_ATTRIBUTE) {
+                let Some(args) = errors.handle(a.parse_args::<TokenTree>().map_err(|_| {
+                    syn::Error::new_spanned(
+                        a,
+                        format!("'{RENAME_ATTRIBUTE}' attribute expects one and only one argument (the new type to use)"),
+                    )
+                })) else {
+                    continue;
+                };
+                new_type = Some(quote! { #args });
+                if !overriden_wrapping {
                     wrapping_behavior = false;
-                    overriden_wrapping = true;
-                } else if a.path().is_ident(WRAP_ATTRIBUTE) {
-                    wrapping_behavior = true;
-                    overriden_wrapping = true;
-                } else if a.path().is_ident(CFG_ATTRIBUTE) {
-                    cfg_attribute = Some(a.clone());
                 }
-            });
-        let field_ident = if let Some(ident) = &old_field.ident {
-            quote! {#ident}
-        } else {
-            let i = syn::Index::from(struct_index);
-            quote! {#i}
+            } else if a.path().is_ident(NESTED_ATTRIBUTE) {
+                nested_attribute = Some(a);
+            } else if a.path().is_ident(SKIP_WRAP_ATTRIBUTE) {
+                wrapping_behavior = false;
+                overriden_wrapping = true;
+            } else if a.path().is_ident(WRAP_ATTRIBUTE) {
+                wrapping_behavior = true;
+                overriden_wrapping = true;
+            } else if a.path().is_ident(SKIP_PARSE_ATTRIBUTE) {
+                skip_parse_attribute = Some(a);
+            }
+        }
+        let forwarded_attributes =
+            errors.handle(get_forwarded_attributes(&old_field.attrs));
+        let (Some(is_base_enum), Some(forwarded_attributes)) =
+            (is_base_enum, forwarded_attributes)
+        else {
+            continue;
         };
+        if let Some(a) = nested_attribute {
+            if new_type.is_some() {
+                errors.push(syn::Error::new_spanned(
+                    a,
+                    format!("'{NESTED_ATTRIBUTE}' cannot be combined with '{RENAME_ATTRIBUTE}'"),
+                ));
+                continue;
+            }
+            if errors
+                .handle(a.meta.require_path_only().map_err(|_| {
+                    syn::Error::new_spanned(
+                        a,
+                        format!(
+                            "'{NESTED_ATTRIBUTE}' attribute takes no arguments"
+                        ),
+                    )
+                }))
+                .is_none()
+            {
+                continue;
+            }
+            let Some(nested_type) =
+                errors.handle(get_nested_type(&old_field.ty, is_base_enum))
+            else {
+                continue;
+            };
+            new_type = Some(nested_type);
+            if !overriden_wrapping {
+                wrapping_behavior = false;
+            }
+        }
+        let skip_parse = skip_parse_attribute.is_some();
         let field_options = FieldOptions {
             wrapping_behavior,
+            is_base_enum,
+            in_variant,
             cfg_attribute,
             new_type,
             field_ident,
+            new_field_ident,
+            forwarded_attributes,
+            skip_parse,
         };
+        if let Some(a) = skip_parse_attribute {
+            if a.meta.require_path_only().is_err() {
+                errors.push(syn::Error::new_spanned(
+                    a,
+                    format!(
+                        "'{SKIP_PARSE_ATTRIBUTE}' attribute takes no arguments"
+                    ),
+                ));
+                continue;
+            }
+            if field_options.is_plain() {
+                errors.push(syn::Error::new_spanned(
+                    a,
+                    format!("'{SKIP_PARSE_ATTRIBUTE}' cannot be used on a field that is neither wrapped nor nested, which every layer sets"),
+                ));
+                continue;
+            }
+        }
         for v in &mut *visitors {
             v.visit(global_options, old_field, new_field, &field_options);
         }
     }
-    (orig, new)
 }
 
+/// Whether two derive paths name the same trait, e.g. `Deserialize` and
+/// `serde::Deserialize`.
+fn is_same_derive(a: &Path, b: &Path) -> bool {
+    a.segments.last().map(|s| &s.ident) == b.segments.last().map(|s| &s.ident)
+}
+
+fn is_serde_derive(derive: &Path) -> bool {
+    derive
+        .segments
+        .last()
+        .is_some_and(|s| s.ident == "Serialize" || s.ident == "Deserialize")
+}
+
+/// The traits derived by `input`, ignoring malformed `derive` attributes
+/// which are reported when copying them to the generated structure.
+fn get_derived_traits(input: &DeriveInput) -> Vec<Path> {
+    input
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("derive"))
+        .filter_map(|a| a.parse_args_with(parse_derive_list).ok())
+        .flatten()
+        .collect()
+}
+
+fn parse_derive_list(
+    input: ParseStream,
+) -> syn::Result<Punctuated<Path, Token![,]>> {
+    Punctuated::parse_terminated(input)
+}
+
+/// Removes the derives marked for removal from the `derive` attributes the
+/// generated structure copied from the original one, and returns the
+/// attribute deriving the extra traits it does not derive yet.
 fn get_derive_macros(
-    new: &DeriveInput,
-    extra_derive: &[String],
-) -> TokenStream {
-    let mut extra_derive = extra_derive.iter().collect::<HashSet<_>>();
-    for attributes in &new.attrs {
-        let _ = attributes.parse_nested_meta(|derived_trait| {
-            let derived_trait = derived_trait.path;
-            let full_path = quote! { #derived_trait };
-            extra_derive.remove(&full_path.to_string());
-            Ok(())
-        });
+    new: &mut DeriveInput,
+    extra_derive: &[Path],
+    removed_derive: &[Path],
+) -> syn::Result<TokenStream> {
+    let mut derived = Vec::new();
+    let mut attrs = Vec::with_capacity(new.attrs.len());
+    for attribute in std::mem::take(&mut new.attrs) {
+        if !attribute.path().is_ident("derive") {
+            attrs.push(attribute);
+            continue;
+        }
+        let kept = attribute
+            .parse_args_with(parse_derive_list)?
+            .into_iter()
+            .filter(|d| !removed_derive.iter().any(|r| is_same_derive(d, r)))
+            .collect::<Vec<_>>();
+        if !kept.is_empty() {
+            attrs.push(syn::parse_quote! { #[derive(#(#kept),*)] });
+        }
+        derived.extend(kept);
+    }
+    new.attrs = attrs;
+
+    let left_to_derive = extra_derive
+        .iter()
+        .filter(|d| !removed_derive.iter().any(|r| is_same_derive(d, r)))
+        .filter(|d| !derived.iter().any(|r| is_same_derive(d, r)))
+        .collect::<Vec<_>>();
+    if left_to_derive.is_empty() {
+        return Ok(quote! {});
+    }
+    Ok(quote! { #[derive(#(#left_to_derive),*)] })
+}
+
+/// The attributes that `enumify_attr` attributes forward to the generated
+/// item only, e.g. `#[serde(default)]` for `#[enumify_attr(serde(default))]`.
+fn get_forwarded_attributes(
+    attrs: &[Attribute],
+) -> syn::Result<Vec<Attribute>> {
+    let mut forwarded = Vec::new();
+    let mut errors = ErrorAccumulator::default();
+    for a in attrs {
+        if !a.path().is_ident(FORWARD_ATTRIBUTE) {
+            continue;
+        }
+        let metas = a
+            .parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)
+            .map_err(|_| {
+                syn::Error::new_spanned(
+                    a,
+                    format!("'{FORWARD_ATTRIBUTE}' attribute expects the attributes to forward, e.g. `{FORWARD_ATTRIBUTE}(serde(default))`"),
+                )
+            });
+        if let Some(metas) = errors.handle(metas) {
+            forwarded
+                .extend(metas.into_iter().map(|m| syn::parse_quote! { #[#m] }));
+        }
     }
+    errors.finish(forwarded)
+}
+
+fn remove_forwarding_attributes(attrs: &mut Vec<Attribute>) {
+    attrs.retain(|a| !a.path().is_ident(FORWARD_ATTRIBUTE));
+}
 
-    let mut acc = quote! {};
-    for left_trait_to_derive in extra_derive {
-        let left_trait_to_derive = format_ident!("{left_trait_to_derive}");
-        acc = quote! { # left_trait_to_derive, # acc};
+/// Removes the helper attribute `name` from the item, returning it if it was
+/// used.
+fn take_item_attribute(
+    attrs: &mut Vec<Attribute>,
+    name: &str,
+) -> syn::Result<Option<Attribute>> {
+    let mut attribute = None;
+    let mut errors = ErrorAccumulator::default();
+    attrs.retain(|a| {
+        if !a.path().is_ident(name) {
+            return true;
+        }
+        if attribute.is_some() {
+            errors.push(syn::Error::new_spanned(
+                a,
+                format!("duplicate '{name}' attribute"),
+            ));
+        } else {
+            attribute = Some(a.clone());
+        }
+        false
+    });
+    errors.finish(attribute)
+}
+
+/// Picks the `Applicable`-like traits to implement from the helper
+/// attributes of the item: `enumify_context` names the type of the context,
+/// while `enumify_async` opts into async resolution. Structures resolved
+/// either way cannot be resolved synchronously without a context.
+fn take_apply_flavors(
+    attrs: &mut Vec<Attribute>,
+) -> syn::Result<Vec<ApplyFlavor>> {
+    let context = take_item_attribute(attrs, CONTEXT_ATTRIBUTE)?;
+    let is_async = take_item_attribute(attrs, ASYNC_ATTRIBUTE)?;
+
+    match (context, is_async) {
+        (Some(context), None) => {
+            let ctx = context.parse_args::<Type>().map_err(|_| {
+                syn::Error::new_spanned(
+                    &context,
+                    format!("'{CONTEXT_ATTRIBUTE}' attribute expects one and only one argument (the type of the context)"),
+                )
+            })?;
+            Ok(vec![
+                ApplyFlavor::Contextual(Box::new(ctx.clone())),
+                ApplyFlavor::FallibleContextual(Box::new(ctx)),
+            ])
+        }
+        (None, Some(is_async)) => {
+            is_async.meta.require_path_only().map_err(|_| {
+                syn::Error::new_spanned(
+                    &is_async,
+                    format!("'{ASYNC_ATTRIBUTE}' attribute takes no arguments"),
+                )
+            })?;
+            if cfg!(feature = "async") {
+                Ok(vec![ApplyFlavor::Async])
+            } else {
+                Err(syn::Error::new_spanned(
+                    is_async,
+                    format!("'{ASYNC_ATTRIBUTE}' requires the `async` feature of enumify_struct"),
+                ))
+            }
+        }
+        (Some(_), Some(is_async)) => Err(syn::Error::new_spanned(
+            is_async,
+            format!("'{ASYNC_ATTRIBUTE}' cannot be combined with '{CONTEXT_ATTRIBUTE}'"),
+        )),
+        (None, None) => Ok(vec![ApplyFlavor::Infallible, ApplyFlavor::Fallible]),
     }
+}
 
-    quote! { #[derive(#acc)] }
+/// Takes the helper attribute `name` opting the item into being parsed from
+/// a source, e.g. `enumify_from_env`, returning whether it was used. Enums
+/// have no variant to parse, and `missing_feature` is the disabled feature of
+/// enumify_struct the source needs, if any.
+fn take_source_attribute(
+    attrs: &mut Vec<Attribute>,
+    data: &Data,
+    name: &str,
+    missing_feature: Option<&str>,
+) -> syn::Result<bool> {
+    let Some(attribute) = take_item_attribute(attrs, name)? else {
+        return Ok(false);
+    };
+    attribute.meta.require_path_only().map_err(|_| {
+        syn::Error::new_spanned(
+            &attribute,
+            format!("'{name}' attribute takes no arguments"),
+        )
+    })?;
+    if matches!(data, Data::Enum(_)) {
+        return Err(syn::Error::new_spanned(
+            attribute,
+            format!("'{name}' can only be used on structs"),
+        ));
+    }
+    if let Some(feature) = missing_feature {
+        return Err(syn::Error::new_spanned(
+            attribute,
+            format!(
+                "'{name}' requires the `{feature}` feature of enumify_struct"
+            ),
+        ));
+    }
+    Ok(true)
 }
 
 struct ParsedMacroParameters {
-    target_enum: Option<Ident>,
+    target_enum: Ident,
     new_struct_name: Option<String>,
     default_wrapping: bool,
+    derives: Option<Vec<Path>>,
+    added_derives: Vec<Path>,
+    removed_derives: Vec<Path>,
+    visibility: Option<Visibility>,
 }
 
-impl Parse for ParsedMacroParameters {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
-        let mut out = ParsedMacroParameters {
-            target_enum: None,
+const MACRO_PARAMETERS: &[&str] = &[
+    "target",
+    "name",
+    "wrap_by_default",
+    "derive",
+    "add_derive",
+    "remove_derive",
+    "vis",
+];
+
+impl ParsedMacroParameters {
+    /// The parameters used when the arguments of the macro are invalid, so
+    /// that the fields still get checked and their errors reported as well.
+    fn fallback() -> Self {
+        ParsedMacroParameters {
+            target_enum: Ident::new(
+                "__EnumifyInvalidTarget",
+                Span::call_site(),
+            ),
             new_struct_name: None,
             default_wrapping: true,
-        };
+            derives: None,
+            added_derives: Vec::new(),
+            removed_derives: Vec::new(),
+            visibility: None,
+        }
+    }
+}
 
-        if let Ok(target_enum) = Ident::parse(input) {
-            out.target_enum = Some(target_enum);
-        } else {
-            panic!("{}", input.error("Expected target_enum").to_string());
+impl Parse for ParsedMacroParameters {
+    /// Parses either the positional form, `(TargetEnum, NewName, bool)`, or
+    /// the keyword form, `(target = TargetEnum, name = NewName, ...)`. Both
+    /// can be mixed as long as positional arguments come first.
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Err(
+                input.error("expected the target enum as first argument")
+            );
         }
 
-        if input.parse::<Token![,]>().is_err() {
-            return Ok(out);
-        };
+        let mut target_enum = None;
+        let mut new_struct_name = None;
+        let mut default_wrapping = None;
+        let mut derives = None;
+        let mut added_derives = None;
+        let mut removed_derives = None;
+        let mut visibility = None;
 
-        if let Ok(struct_name) = Ident::parse(input) {
-            out.new_struct_name = Some(struct_name.to_string());
-        } else {
-            return Ok(out);
-        };
+        let mut position = 0;
+        let mut seen_keyword = false;
+        while !input.is_empty() {
+            let is_keyword = input.peek(Ident::peek_any)
+                && (input.peek2(Token![=]) || input.peek2(syn::token::Paren));
+            if !is_keyword {
+                if seen_keyword {
+                    return Err(input.error(
+                        "positional arguments must come before keyword arguments",
+                    ));
+                }
+                match position {
+                    0 => {
+                        target_enum =
+                            Some(Ident::parse(input).map_err(|_| {
+                                input.error(
+                                "expected the target enum as first argument",
+                            )
+                            })?)
+                    }
+                    1 => {
+                        new_struct_name = Some(Ident::parse(input)?.to_string())
+                    }
+                    2 => {
+                        default_wrapping =
+                            Some(syn::LitBool::parse(input)?.value)
+                    }
+                    _ => return Err(input.error("unexpected argument")),
+                }
+                position += 1;
+            } else {
+                seen_keyword = true;
+                let key = Ident::parse_any(input)?;
+                let already_set = match key.to_string().as_str() {
+                    "target" => {
+                        input.parse::<Token![=]>()?;
+                        target_enum.replace(input.parse()?).is_some()
+                    }
+                    "name" => {
+                        input.parse::<Token![=]>()?;
+                        let name = Ident::parse(input)?.to_string();
+                        new_struct_name.replace(name).is_some()
+                    }
+                    "wrap_by_default" => {
+                        input.parse::<Token![=]>()?;
+                        let wrapping = syn::LitBool::parse(input)?.value;
+                        default_wrapping.replace(wrapping).is_some()
+                    }
+                    "derive" => {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        let list = parse_derive_list(&content)?;
+                        derives.replace(list.into_iter().collect()).is_some()
+                    }
+                    "add_derive" => {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        let list = parse_derive_list(&content)?;
+                        added_derives.replace(list).is_some()
+                    }
+                    "remove_derive" => {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        let list = parse_derive_list(&content)?;
+                        removed_derives.replace(list).is_some()
+                    }
+                    "vis" => {
+                        input.parse::<Token![=]>()?;
+                        visibility.replace(input.parse()?).is_some()
+                    }
+                    _ => {
+                        let expected = MACRO_PARAMETERS
+                            .iter()
+                            .map(|p| format!("`{p}`"))
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        return Err(syn::Error::new(
+                            key.span(),
+                            format!("unknown argument `{key}`, expected one of {expected}"),
+                        ));
+                    }
+                };
+                if already_set {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("duplicate argument `{key}`"),
+                    ));
+                }
+            }
 
-        if input.parse::<Token![,]>().is_err() {
-            return Ok(out);
-        };
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
 
-        if let Ok(wrapping) = syn::LitBool::parse(input) {
-            out.default_wrapping = wrapping.value;
-        } else {
-            return Ok(out);
+        let Some(target_enum) = target_enum else {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "missing the target enum, expected `target = ...`",
+            ));
         };
+        Ok(ParsedMacroParameters {
+            target_enum,
+            new_struct_name,
+            default_wrapping: default_wrapping.unwrap_or(true),
+            derives,
+            added_derives: added_derives
+                .map(|d| d.into_iter().collect())
+                .unwrap_or_default(),
+            removed_derives: removed_derives
+                .map(|d| d.into_iter().collect())
+                .unwrap_or_default(),
+            visibility,
+        })
+    }
+}
+
+/// The enumified type of a field marked `enumify_nested`, resolved through
+/// the `Enumify` implementation of its type (or of the type wrapped in the
+/// target enum, if the field already is one).
+fn get_nested_type(t: &Type, is_base_enum: bool) -> syn::Result<TokenStream> {
+    let mut nested = t;
+    if is_base_enum {
+        nested = get_wrapped_type(t).ok_or_else(|| {
+            syn::Error::new_spanned(
+                t,
+                format!("'{NESTED_ATTRIBUTE}' expects the target enum to wrap the nested type"),
+            )
+        })?;
+    }
+    Ok(quote! { <#nested as enumify_struct::Enumify>::Enumified })
+}
 
-        Ok(out)
+/// The first type argument of the target enum `t`, e.g. `T` for `Option<T>`.
+fn get_wrapped_type(t: &Type) -> Option<&Type> {
+    match t {
+        Type::Paren(type_paren) => get_wrapped_type(&type_paren.elem),
+        Type::Path(type_path) => {
+            let segment = type_path.path.segments.last()?;
+            let syn::PathArguments::AngleBracketed(args) = &segment.arguments
+            else {
+                return None;
+            };
+            args.args.iter().find_map(|arg| match arg {
+                syn::GenericArgument::Type(t) => Some(t),
+                _ => None,
+            })
+        }
+        _ => None,
     }
 }
 
@@ -362,32 +2627,44 @@ fn is_path_enum(p: &Path, target_enum: &Ident) -> bool {
         .unwrap_or(false)
 }
 
-fn is_type_target_enum(t: &Type, target_enum: &Ident) -> bool {
+fn is_type_target_enum(t: &Type, target_enum: &Ident) -> syn::Result<bool> {
     macro_rules! wtf {
         ($reason : tt) => {
-            panic!(
-                "Using enumify_struct for a struct containing a {} is not valid.",
-                $reason
-            )
+            Err(syn::Error::new_spanned(
+                t,
+                format!(
+                    "Using enumify_struct for a struct containing a {} is not valid.",
+                    $reason
+                ),
+            ))
         };
     }
 
     match &t {
         // real work
-        Type::Path(type_path) => is_path_enum(&type_path.path, target_enum),
-        Type::Array(_) | Type::Tuple(_) => false,
+        Type::Path(type_path) => {
+            Ok(is_path_enum(&type_path.path, target_enum))
+        }
+        Type::Array(_) | Type::Tuple(_) => Ok(false),
         Type::Paren(type_paren) => {
             is_type_target_enum(&type_paren.elem, target_enum)
         }
 
         // No clue what to do with those
         Type::ImplTrait(_) | Type::TraitObject(_) => {
-            panic!(
-                "Might already be the target_enum, but there is no way to tell"
-            )
+            Err(syn::Error::new_spanned(
+                t,
+                "Might already be the target_enum, but there is no way to tell",
+            ))
         }
-        Type::Infer(_) => panic!("If you cannot tell, neither can I"),
-        Type::Macro(_) => panic!("Don't think I can handle this easily..."),
+        Type::Infer(_) => Err(syn::Error::new_spanned(
+            t,
+            "If you cannot tell, neither can I",
+        )),
+        Type::Macro(_) => Err(syn::Error::new_spanned(
+            t,
+            "Don't think I can handle this easily...",
+        )),
 
         // Makes no sense to use those in an EnumifyStruct
         Type::Reference(_) => wtf!("reference"),
@@ -396,16 +2673,22 @@ fn is_type_target_enum(t: &Type, target_enum: &Ident) -> bool {
         Type::Ptr(_) => wtf!("pointer"),
         Type::BareFn(_) => wtf!("function pointer"),
 
-        _ => panic!("Open an issue please"),
+        _ => Err(syn::Error::new_spanned(
+            t,
+            "This type is not supported by enumify_struct, open an issue please",
+        )),
     }
 }
 
 struct GlobalOptions {
     new_struct_name: String,
     target_enum: Ident,
-    extra_derive: Vec<String>,
+    extra_derive: Vec<Path>,
+    removed_derive: Vec<Path>,
     default_wrapping_behavior: bool,
     make_fields_public: bool,
+    new_struct_visibility: Option<Visibility>,
+    derives_serde: bool,
 }
 
 impl GlobalOptions {
@@ -417,16 +2700,55 @@ impl GlobalOptions {
             "Enumified".to_owned() + &struct_definition.ident.to_string()
         });
         let default_wrapping_behavior = attr.default_wrapping;
-        let target_enum = attr.target_enum.unwrap();
+        let mut extra_derive = attr.derives.unwrap_or_else(|| {
+            vec![
+                syn::parse_quote!(Clone),
+                syn::parse_quote!(PartialEq),
+                syn::parse_quote!(Debug),
+            ]
+        });
+        // Only the traits the generated structure is asked to derive, so that
+        // enabling features elsewhere does not change it
+        let derives_serde = get_derived_traits(struct_definition)
+            .iter()
+            .chain(&extra_derive)
+            .chain(&attr.added_derives)
+            .filter(|d| {
+                !attr.removed_derives.iter().any(|r| is_same_derive(d, r))
+            })
+            .any(is_serde_derive);
+        for added in attr.added_derives {
+            if !extra_derive.iter().any(|d| is_same_derive(d, &added)) {
+                extra_derive.push(added);
+            }
+        }
+        let target_enum = attr.target_enum;
         GlobalOptions {
             new_struct_name,
             target_enum,
-            extra_derive: vec!["Clone", "PartialEq", "Debug"]
-                .into_iter()
-                .map(|s| s.to_owned())
-                .collect(),
+            extra_derive,
+            removed_derive: attr.removed_derives,
             default_wrapping_behavior,
             make_fields_public: true,
+            new_struct_visibility: attr.visibility,
+            derives_serde,
+        }
+    }
+}
+
+/// Maps the original structure to the generated one, which lets fields marked
+/// `enumify_nested` find the type to use.
+fn get_enumify_implementation(
+    orig: &DeriveInput,
+    new: &DeriveInput,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) =
+        orig.generics.split_for_impl();
+    let orig_name = &orig.ident;
+    let new_name = &new.ident;
+    quote! {
+        impl #impl_generics enumify_struct::Enumify for #orig_name #ty_generics #where_clause {
+            type Enumified = #new_name #ty_generics;
         }
     }
 }
@@ -439,39 +2761,134 @@ pub struct EnumifyStructOutput {
 pub fn enumify_struct(
     attr: TokenStream,
     input: TokenStream,
-) -> EnumifyStructOutput {
-    let derive_input = syn::parse2::<DeriveInput>(input).unwrap();
-    let macro_params =
-        GlobalOptions::new(syn::parse2::<_>(attr).unwrap(), &derive_input);
-
-    let mut applicable_impl_generator = GenerateApplicableImplVisitor::new();
-
-    let mut visitors = [
-        &mut RemoveHelperAttributesVisitor as &mut dyn EnumFieldVisitor,
-        &mut SetNewFieldVisibilityVisitor,
-        &mut SetNewFieldTypeVisitor,
-        &mut applicable_impl_generator,
+) -> syn::Result<EnumifyStructOutput> {
+    let mut derive_input = syn::parse2::<DeriveInput>(input)?;
+    // Invalid arguments or item attributes do not keep the fields from
+    // being checked, so that every error is reported at once
+    let mut errors = ErrorAccumulator::default();
+    let parameters = errors
+        .handle(syn::parse2::<ParsedMacroParameters>(attr))
+        .unwrap_or_else(ParsedMacroParameters::fallback);
+    let macro_params = GlobalOptions::new(parameters, &derive_input);
+    let flavors = errors
+        .handle(take_apply_flavors(&mut derive_input.attrs))
+        .unwrap_or_else(|| {
+            vec![ApplyFlavor::Infallible, ApplyFlavor::Fallible]
+        });
+    let from_env = errors
+        .handle(take_source_attribute(
+            &mut derive_input.attrs,
+            &derive_input.data,
+            FROM_ENV_ATTRIBUTE,
+            None,
+        ))
+        .unwrap_or_default();
+    let clap = errors
+        .handle(take_source_attribute(
+            &mut derive_input.attrs,
+            &derive_input.data,
+            CLAP_ATTRIBUTE,
+            (!cfg!(feature = "clap")).then_some("clap"),
+        ))
+        .unwrap_or_default();
+    let forwarded_attributes = errors
+        .handle(get_forwarded_attributes(&derive_input.attrs))
+        .unwrap_or_default();
+    remove_forwarding_attributes(&mut derive_input.attrs);
+    let mut impl_generators = flavors
+        .into_iter()
+        .map(|flavor| GenerateApplicableImplVisitor::new(&derive_input, flavor))
+        .collect::<Vec<_>>();
+
+    // The tracked method goes within the infallible implementation
+    let has_infallible = impl_generators
+        .iter()
+        .any(|g| matches!(g.flavor, ApplyFlavor::Infallible));
+    let mut tracked_generator = has_infallible.then(|| {
+        GenerateApplicableImplVisitor::new(&derive_input, ApplyFlavor::Tracked)
+    });
+
+    let mut remove_helper_attributes = RemoveHelperAttributesVisitor;
+    let mut set_new_field_visibility = SetNewFieldVisibilityVisitor;
+    let mut set_new_field_type = SetNewFieldTypeVisitor;
+    let mut visitors = vec![
+        &mut remove_helper_attributes as &mut dyn EnumFieldVisitor,
+        &mut set_new_field_visibility,
+        &mut set_new_field_type,
     ];
+    for generator in &mut impl_generators {
+        visitors.push(generator);
+    }
+    if let Some(generator) = &mut tracked_generator {
+        visitors.push(generator);
+    }
+    let mut from_base_generator =
+        GenerateFromBaseImplVisitor::new(&derive_input);
+    visitors.push(&mut from_base_generator);
+    let mut merge_generator = GenerateMergeImplVisitor::new();
+    visitors.push(&mut merge_generator);
+    let mut from_env_generator =
+        GenerateFromEnvImplVisitor::new(&derive_input, from_env);
+    visitors.push(&mut from_env_generator);
+    let mut clap_generator = GenerateClapImplVisitor::new(&derive_input, clap);
+    visitors.push(&mut clap_generator);
 
-    let (orig, mut new) =
-        visit_fields(&mut visitors, &macro_params, &derive_input);
+    let visited = errors.handle(visit_fields(
+        &mut visitors,
+        &macro_params,
+        &derive_input,
+    ));
+    let (orig, mut new) = errors
+        .finish(visited)?
+        .expect("visiting the fields only fails with an error");
 
     new.ident = Ident::new(&macro_params.new_struct_name, new.ident.span());
+    if let Some(vis) = &macro_params.new_struct_visibility {
+        new.vis = vis.clone();
+    }
+    retain_serde_attributes(&mut new.attrs, macro_params.derives_serde);
+    new.attrs.extend(forwarded_attributes);
 
-    let applicable_impl =
-        applicable_impl_generator.get_implementation(&derive_input, &new);
+    let derives = get_derive_macros(
+        &mut new,
+        &macro_params.extra_derive,
+        &macro_params.removed_derive,
+    )?;
 
-    let derives = get_derive_macros(&new, &macro_params.extra_derive);
+    let tracked_method = tracked_generator
+        .map(|generator| generator.get_implementation(&derive_input, &new));
+    let applicable_impls = impl_generators.into_iter().map(|mut generator| {
+        if let (ApplyFlavor::Infallible, Some(tracked_method)) =
+            (&generator.flavor, &tracked_method)
+        {
+            generator.extra_items = tracked_method.clone();
+        }
+        generator.get_implementation(&derive_input, &new)
+    });
+
+    let enumify_impl = get_enumify_implementation(&derive_input, &new);
+    let from_base_impl =
+        from_base_generator.get_implementation(&derive_input, &new);
+    let merge_impl = merge_generator.get_implementation(&derive_input, &new);
+    let from_env_impl =
+        from_env_generator.get_implementation(&derive_input, &new)?;
+    let clap_impl = clap_generator.get_implementation(&derive_input, &new)?;
 
     let generated = quote! {
         #derives
         #new
 
-        #applicable_impl
+        #enumify_impl
+        #from_base_impl
+        #merge_impl
+        #from_env_impl
+        #clap_impl
+
+        #(#applicable_impls)*
     };
 
-    EnumifyStructOutput {
+    Ok(EnumifyStructOutput {
         original: quote! { #orig },
         generated,
-    }
+    })
 }
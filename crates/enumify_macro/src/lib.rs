@@ -9,7 +9,10 @@ pub fn enumify_struct(
     attr: proc_macro::TokenStream,
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
-    let out = enum_struct::enumify_struct(attr.into(), input.into());
+    let out = match enum_struct::enumify_struct(attr.into(), input.into()) {
+        Ok(out) => out,
+        Err(e) => return e.to_compile_error().into(),
+    };
     let original = out.original;
     let generated = out.generated;
     proc_macro::TokenStream::from(quote! {
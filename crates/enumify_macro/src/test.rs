@@ -12,7 +12,8 @@ fn basic_gen() {
                 baz: String,
             }
         ),
-    );
+    )
+    .unwrap();
 }
 
 #[test]
@@ -25,7 +26,8 @@ fn with_nested() {
                 bar: Bar,
             }
         ),
-    );
+    )
+    .unwrap();
 }
 
 #[test]
@@ -39,7 +41,94 @@ fn with_redundant_derive() {
                 baz: String,
             }
         ),
-    );
+    )
+    .unwrap();
+}
+
+#[test]
+fn with_configured_derives() {
+    let out = enumify_struct(
+        quote!(
+            BasicEnum,
+            add_derive(Default, serde::Deserialize, Clone),
+            remove_derive(PartialEq),
+        ),
+        quote!(
+            #[derive(PartialEq, Clone, Deserialize)]
+            struct Foo {
+                bar: u8,
+            }
+        ),
+    )
+    .unwrap();
+    let generated = out.generated.to_string();
+    assert!(generated.starts_with(
+        &quote!(
+            #[derive(Debug, Default)]
+            #[derive(Clone, Deserialize)]
+            struct EnumifiedFoo
+        )
+        .to_string()
+    ));
+
+    // The original structure keeps its own derives
+    let original = out.original.to_string();
+    assert!(original.starts_with(
+        &quote!(#[derive(PartialEq, Clone, Deserialize)]).to_string()
+    ));
+}
+
+#[test]
+fn derives_keep_declaration_order() {
+    let out = enumify_struct(
+        quote!(
+            BasicEnum,
+            derive(Serialize, Debug, Clone, Hash, Eq),
+            add_derive(PartialOrd, Default),
+        ),
+        quote!(
+            #[derive(Ord, PartialEq)]
+            struct Foo {
+                bar: u8,
+            }
+        ),
+    )
+    .unwrap();
+    assert!(out.generated.to_string().starts_with(
+        &quote!(
+            #[derive(Serialize, Debug, Clone, Hash, Eq, PartialOrd, Default)]
+            #[derive(Ord, PartialEq)]
+            struct EnumifiedFoo
+        )
+        .to_string()
+    ));
+}
+
+#[test]
+fn expansion_is_deterministic() {
+    let expand = || {
+        let out = enumify_struct(
+            quote!(BasicEnum, add_derive(Hash, Eq, Default, PartialOrd)),
+            quote!(
+                #[derive(Clone, Ord, serde::Serialize)]
+                enum Foo {
+                    Bar {
+                        bar: u8,
+                        #[enumify_rename(OptionalBaz)]
+                        baz: Baz,
+                    },
+                    Qux(#[cfg(any())] u8, String),
+                }
+            ),
+        )
+        .unwrap();
+        (out.original.to_string(), out.generated.to_string())
+    };
+
+    let first = expand();
+    for _ in 0..16 {
+        assert_eq!(expand(), first);
+    }
 }
 
 #[test]
@@ -53,7 +142,8 @@ fn with_unrelated_derive() {
                 baz: String,
             }
         ),
-    );
+    )
+    .unwrap();
 }
 
 #[test]
@@ -65,7 +155,8 @@ fn with_enum() {
                 bar: Option<u8>,
             }
         ),
-    );
+    )
+    .unwrap();
 }
 
 #[test]
@@ -80,7 +171,8 @@ fn with_cfg_attributes() {
                 baz: u8,
             }
         ),
-    );
+    )
+    .unwrap();
 }
 
 #[test]
@@ -108,5 +200,878 @@ fn with_nested_option_skip_wrap() {
                 inner5: Option<Inner>,
             }
         ),
+    )
+    .unwrap();
+}
+
+#[test]
+fn with_enumify_nested() {
+    let out = enumify_struct(
+        quote!(Option),
+        quote!(
+            struct Foo {
+                #[enumify_nested]
+                bar: Bar,
+                #[enumify_nested]
+                baz: Option<Baz>,
+            }
+        ),
+    )
+    .unwrap();
+    let generated = out.generated.to_string();
+    assert!(generated.contains(
+        &quote!(bar: <Bar as enumify_struct::Enumify>::Enumified).to_string()
+    ));
+    assert!(generated.contains(
+        &quote!(baz: <Baz as enumify_struct::Enumify>::Enumified).to_string()
+    ));
+    assert!(generated.contains(
+        &quote!(impl enumify_struct::Enumify for Foo {
+            type Enumified = EnumifiedFoo;
+        })
+        .to_string()
+    ));
+}
+
+#[test]
+fn with_keyword_arguments() {
+    let out = enumify_struct(
+        quote!(
+            target = BasicEnum,
+            name = PartialFoo,
+            wrap_by_default = false,
+            derive(Debug),
+            vis = pub(crate),
+        ),
+        quote!(
+            pub struct Foo {
+                bar: u8,
+            }
+        ),
+    )
+    .unwrap();
+    let generated = out.generated.to_string();
+    assert!(generated.starts_with(&quote!(#[derive(Debug)]).to_string()));
+    assert!(generated.contains(
+        &quote!(
+            pub(crate) struct PartialFoo {
+                pub bar: u8,
+            }
+        )
+        .to_string()
+    ));
+}
+
+#[test]
+fn with_mixed_arguments() {
+    let out = enumify_struct(
+        quote!(BasicEnum, PartialFoo, vis = pub(crate)),
+        quote!(
+            struct Foo {
+                bar: u8,
+            }
+        ),
+    )
+    .unwrap();
+    let generated = out.generated.to_string();
+    assert!(generated.contains(
+        &quote!(
+            pub(crate) struct PartialFoo {
+                pub bar: BasicEnum<u8>,
+            }
+        )
+        .to_string()
+    ));
+}
+
+#[test]
+fn with_forwarded_attributes() {
+    let out = enumify_struct(
+        quote!(BasicEnum),
+        quote!(
+            #[enumify_attr(serde(untagged), doc = "Generated")]
+            enum Foo {
+                #[enumify_attr(serde(rename = "bar"))]
+                Bar {
+                    #[serde(rename = "renamed")]
+                    #[enumify_attr(serde(default))]
+                    bar: u8,
+                },
+            }
+        ),
+    )
+    .unwrap();
+    assert_eq!(
+        out.original.to_string(),
+        quote!(
+            enum Foo {
+                Bar {
+                    #[serde(rename = "renamed")]
+                    bar: u8,
+                },
+            }
+        )
+        .to_string()
+    );
+    assert!(out.generated.to_string().contains(
+        &quote!(
+            #[serde(untagged)]
+            #[doc = "Generated"]
+            enum EnumifiedFoo {
+                #[serde(rename = "bar")]
+                Bar {
+                    #[serde(default)]
+                    bar: BasicEnum<u8>,
+                },
+            }
+        )
+        .to_string()
+    ));
+}
+
+#[test]
+fn with_generics() {
+    let out = enumify_struct(
+        quote!(BasicEnum),
+        quote!(
+            struct Foo<T, const N: usize = 3>
+            where
+                T: Copy,
+            {
+                bar: T,
+                baz: [u8; N],
+                #[enumify_nested]
+                nested: Bar<T>,
+                plain: u8,
+            }
+        ),
+    )
+    .unwrap();
+    let expected_impl = quote!(
+        impl<T, const N: usize> enumify_struct::Applicable for EnumifiedFoo<T, N>
+        where
+            T: Copy,
+            <Bar<T> as enumify_struct::Enumify>::Enumified:
+                enumify_struct::Applicable<Base = Bar<T> >
+    );
+    let generated = out.generated.to_string();
+    assert!(
+        generated.contains(&expected_impl.to_string()),
+        "{generated}"
+    );
+}
+
+#[test]
+fn with_skipped_fields() {
+    let out = enumify_struct(
+        quote!(BasicEnum),
+        quote!(
+            struct Foo(u8, #[enumify_skip] &'static str, String);
+        ),
+    )
+    .unwrap();
+    assert_eq!(
+        out.original.to_string(),
+        quote!(
+            struct Foo(u8, &'static str, String);
+        )
+        .to_string()
+    );
+    let generated = out.generated.to_string();
+    assert!(generated.contains(
+        &quote!(
+            struct EnumifiedFoo(pub BasicEnum<u8>, pub BasicEnum<String>);
+        )
+        .to_string()
+    ));
+    assert!(generated.contains(&quote!(t.2 = ).to_string()));
+    assert!(generated.contains(&quote!(let field = self.1;).to_string()));
+}
+
+#[test]
+fn unit_struct_gen() {
+    let out = enumify_struct(
+        quote!(BasicEnum),
+        quote!(
+            struct Foo;
+        ),
+    )
+    .unwrap();
+    let generated = out.generated.to_string();
+    assert!(generated.contains(
+        &quote!(
+            struct EnumifiedFoo;
+        )
+        .to_string()
+    ));
+    assert!(generated.contains(
+        &quote!(
+            impl enumify_struct::Applicable for EnumifiedFoo {
+                type Base = Foo;
+
+                fn apply_to(self, t: &mut Self::Base) {}
+
+                fn apply_to_tracked(
+                    self,
+                    t: &mut Self::Base,
+                    path: &str,
+                    record: &mut dyn FnMut(&str),
+                ) {
+                }
+            }
+        )
+        .to_string()
+    ));
+}
+
+#[test]
+fn with_from_base() {
+    let out = enumify_struct(
+        quote!(BasicEnum),
+        quote!(
+            struct Foo(#[enumify_skip] u8, String, #[enumify_nested] Bar);
+        ),
+    )
+    .unwrap();
+    assert!(out.generated.to_string().contains(
+        &quote!(
+            impl From<Foo> for EnumifiedFoo
+            where
+                for<'__enumify> BasicEnum<String>: enumify_struct::FromBase<String>,
+                for<'__enumify> <Bar as enumify_struct::Enumify>::Enumified: From<Bar>
+            {
+                fn from(t: Foo) -> Self {
+                    Self {
+                        0: <BasicEnum<String> as enumify_struct::FromBase<String> >::from_base(t.1),
+                        1: < <Bar as enumify_struct::Enumify>::Enumified as From<Bar>>::from(t.2),
+                    }
+                }
+            }
+        )
+        .to_string()
+    ));
+}
+
+#[test]
+fn with_merge() {
+    let out = enumify_struct(
+        quote!(BasicEnum),
+        quote!(
+            struct Foo {
+                bar: u8,
+                #[enumify_skip_wrap]
+                baz: String,
+            }
+        ),
+    )
+    .unwrap();
+    assert!(out.generated.to_string().contains(
+        &quote!(
+            impl enumify_struct::Mergeable for EnumifiedFoo
+            where
+                for<'__enumify> BasicEnum<u8>: enumify_struct::Mergeable
+            {
+                fn merge(self, other: Self) -> Self {
+                    Self {
+                        bar: <BasicEnum<u8> as enumify_struct::Mergeable>::merge(self.bar, other.bar),
+                        baz: other.baz,
+                    }
+                }
+            }
+        )
+        .to_string()
+    ));
+}
+
+#[test]
+fn with_provenance() {
+    let out = enumify_struct(
+        quote!(BasicEnum),
+        quote!(
+            struct Foo {
+                bar: u8,
+                #[enumify_nested]
+                baz: Baz,
+            }
+        ),
+    )
+    .unwrap();
+    let generated = out.generated.to_string();
+    assert!(generated.contains(
+        &quote!(if let Some(value) = {
+            let field = self.bar;
+            enumify_struct::__private::Resolve::<u8, _>::resolve(field)
+        } {
+            t.bar = value;
+            record(&enumify_struct::__private::field_path(path, "bar"));
+        })
+        .to_string()
+    ));
+    assert!(generated.contains(
+        &quote!(enumify_struct::Applicable::apply_to_tracked(
+            self.baz,
+            &mut t.baz,
+            &enumify_struct::__private::field_path(path, "baz"),
+            record
+        ))
+        .to_string()
+    ));
+}
+
+#[test]
+fn with_from_env() {
+    let out = enumify_struct(
+        quote!(BasicEnum),
+        quote!(
+            #[enumify_from_env]
+            struct Foo<T> {
+                #[enumify_nested]
+                bar: Bar,
+                #[enumify_skip_wrap]
+                baz: T,
+                #[enumify_skip_parse]
+                qux: Vec<u8>,
+            }
+        ),
+    )
+    .unwrap();
+    // Only generic fields are bounded, concrete ones being checked where
+    // they are read
+    assert!(out.generated.to_string().contains(
+        &quote!(
+            impl<T> enumify_struct::FromEnv for EnumifiedFoo<T>
+            where
+                T: std::str::FromStr,
+                <T as std::str::FromStr>::Err: Into<Box<dyn std::error::Error + Send + Sync> >
+            {
+                fn from_env_with(
+                    prefix: &str,
+                    var: &dyn Fn(&str) -> Option<String>,
+                ) -> Result<Self, enumify_struct::ResolveError> {
+                    Ok(Self {
+                        bar: < <Bar as enumify_struct::Enumify>::Enumified as enumify_struct::FromEnv>::from_env_with(
+                            &enumify_struct::__private::nested_env_prefix(prefix, "BAR"),
+                            var,
+                        )?,
+                        baz: enumify_struct::__private::parse_required_env_var::<T>(
+                            var,
+                            &enumify_struct::__private::env_var_name(prefix, "BAZ")
+                        )?,
+                        qux: <BasicEnum<Vec<u8> > as Default>::default(),
+                    })
+                }
+            }
+        )
+        .to_string()
+    ));
+
+    // Only the structures opting in implement it
+    let out = enumify_struct(
+        quote!(BasicEnum),
+        quote!(
+            struct Foo {
+                bar: u8,
+            }
+        ),
+    )
+    .unwrap();
+    assert!(!out.generated.to_string().contains("FromEnv"));
+}
+
+#[test]
+#[cfg(feature = "clap")]
+fn with_clap() {
+    let out = enumify_struct(
+        quote!(BasicEnum),
+        quote!(
+            #[enumify_clap]
+            struct Foo<T> {
+                /// The bar
+                bar_baz: T,
+                #[enumify_skip_wrap]
+                retries: u8,
+                #[enumify_skip_parse]
+                tags: Vec<String>,
+            }
+        ),
+    )
+    .unwrap();
+    let generated = out.generated.to_string();
+    assert!(generated.contains(
+        &quote!(
+            impl<T> enumify_struct::__private::PrefixedArgs for EnumifiedFoo<T>
+            where
+                BasicEnum<T>: enumify_struct::FromBase<T>,
+                BasicEnum<T>: Default,
+                T: std::str::FromStr,
+                <T as std::str::FromStr>::Err: Into<Box<dyn std::error::Error + Send + Sync> >,
+                T: Clone + Send + Sync + 'static
+        )
+        .to_string()
+    ));
+    assert!(generated.contains(
+        &quote!(
+            let cmd = cmd.arg(
+                enumify_struct::__private::arg::<T>(prefix, "bar-baz", "The bar")
+            );
+            let cmd = cmd.arg(
+                enumify_struct::__private::arg::<u8>(prefix, "retries", "")
+                    .required(!for_update)
+            );
+            cmd
+        )
+        .to_string()
+    ));
+    // Flags that are absent leave the fields that every layer sets as is
+    assert!(generated.contains(
+        &quote!(
+            retries: enumify_struct::__private::get_required_arg::<u8>(
+                matches, prefix, "retries"
+            )?,
+            tags: <BasicEnum<Vec<String> > as Default>::default(),
+        )
+        .to_string()
+    ));
+    assert!(generated.contains(
+        &quote!({
+            if let Some(value) = enumify_struct::__private::get_arg::<u8>(
+                matches, prefix, "retries"
+            ) {
+                self.retries = value;
+            }
+        } {} Ok(()))
+        .to_string()
+    ));
+    assert!(generated.contains(
+        &quote!(
+            impl<T> enumify_struct::__private::clap::Args for EnumifiedFoo<T>
+            where
+                BasicEnum<T>: enumify_struct::FromBase<T>,
+        )
+        .to_string()
+    ));
+
+    // Only the structures opting in implement it
+    let out = enumify_struct(
+        quote!(BasicEnum),
+        quote!(
+            struct Foo {
+                bar: u8,
+            }
+        ),
+    )
+    .unwrap();
+    assert!(!out.generated.to_string().contains("PrefixedArgs"));
+}
+
+#[test]
+fn with_serde() {
+    let out = enumify_struct(
+        quote!(BasicEnum),
+        quote!(
+            #[derive(serde::Deserialize)]
+            #[serde(rename_all = "kebab-case", default)]
+            struct Foo {
+                #[serde(rename = "Bar", deserialize_with = "parse_bar")]
+                bar: u8,
+                #[serde(skip)]
+                baz: String,
+                #[enumify_nested]
+                #[serde(default)]
+                nested: Bar,
+            }
+        ),
+    )
+    .unwrap();
+    // Only `Deserialize`, which the original structure derives
+    let generated = out.generated.to_string();
+    assert!(generated
+        .starts_with(&quote!(#[derive(Clone, PartialEq, Debug)]).to_string()));
+    assert!(generated.contains(
+        &quote!(
+            #[derive(serde::Deserialize)]
+            #[serde(rename_all = "kebab-case")]
+            struct EnumifiedFoo {
+                #[serde(rename = "Bar")]
+                #[serde(default)]
+                pub bar: BasicEnum<u8>,
+                #[serde(skip)]
+                #[serde(default)]
+                pub baz: BasicEnum<String>,
+                pub nested: <Bar as enumify_struct::Enumify>::Enumified,
+            }
+        )
+        .to_string()
+    ));
+}
+
+#[test]
+fn enum_gen() {
+    enumify_struct(
+        quote!(BasicEnum),
+        quote!(
+            enum Foo {
+                Bar {
+                    bar: u8,
+                    baz: String,
+                },
+                Baz(u8, #[enumify_rename(OptionalBar)] Bar),
+                #[cfg(any())]
+                Qux {
+                    qux: u8,
+                },
+                Unit,
+            }
+        ),
+    )
+    .unwrap();
+}
+
+#[test]
+fn empty_enum_gen() {
+    enumify_struct(
+        quote!(BasicEnum),
+        quote!(
+            enum Foo {}
+        ),
+    )
+    .unwrap();
+}
+
+#[test]
+fn with_context() {
+    let out = enumify_struct(
+        quote!(BasicEnum),
+        quote!(
+            #[enumify_context(Registry)]
+            struct Foo {
+                bar: u8,
+            }
+        ),
+    )
+    .unwrap();
+    assert!(!out.original.to_string().contains("enumify_context"));
+    assert!(!out.generated.to_string().contains("enumify_context"));
+}
+
+fn expect_errors(
+    attr: proc_macro2::TokenStream,
+    input: proc_macro2::TokenStream,
+) -> Vec<String> {
+    match enumify_struct(attr, input) {
+        Ok(_) => panic!("expected enumify_struct to fail"),
+        Err(e) => e.into_iter().map(|e| e.to_string()).collect(),
+    }
+}
+
+#[test]
+fn error_missing_target_enum() {
+    let errors = expect_errors(
+        quote!(),
+        quote!(
+            struct Foo {
+                bar: u8,
+            }
+        ),
+    );
+    assert_eq!(
+        errors,
+        ["unexpected end of input, expected the target enum as first argument"]
+    );
+}
+
+#[test]
+fn error_not_a_struct() {
+    let errors = expect_errors(
+        quote!(BasicEnum),
+        quote!(
+            union Foo {
+                bar: u8,
+            }
+        ),
+    );
+    assert_eq!(errors, ["enumify_struct only works for structs and enums"]);
+}
+
+#[test]
+fn error_invalid_rename() {
+    let errors = expect_errors(
+        quote!(BasicEnum),
+        quote!(
+            struct Foo {
+                #[enumify_rename(Bar, Baz)]
+                bar: Bar,
+            }
+        ),
+    );
+    assert_eq!(
+        errors,
+        ["'enumify_rename' attribute expects one and only one argument (the new type to use)"]
+    );
+}
+
+#[test]
+fn error_unsupported_field_types() {
+    let errors = expect_errors(
+        quote!(BasicEnum),
+        quote!(
+            struct Foo {
+                a: impl Clone,
+                b: _,
+                c: m!(),
+                d: &'static str,
+                e: !,
+                f: [u8],
+                g: *const u8,
+                h: fn(),
+            }
+        ),
+    );
+    assert_eq!(
+        errors,
+        [
+            "Might already be the target_enum, but there is no way to tell",
+            "If you cannot tell, neither can I",
+            "Don't think I can handle this easily...",
+            "Using enumify_struct for a struct containing a reference is not valid.",
+            "Using enumify_struct for a struct containing a never-type is not valid.",
+            "Using enumify_struct for a struct containing a slice is not valid.",
+            "Using enumify_struct for a struct containing a pointer is not valid.",
+            "Using enumify_struct for a struct containing a function pointer is not valid.",
+        ]
+    );
+}
+
+#[test]
+fn error_invalid_context() {
+    let errors = expect_errors(
+        quote!(BasicEnum),
+        quote!(
+            #[enumify_context(Registry)]
+            #[enumify_context]
+            struct Foo {
+                bar: u8,
+            }
+        ),
+    );
+    assert_eq!(errors, ["duplicate 'enumify_context' attribute"]);
+
+    let errors = expect_errors(
+        quote!(BasicEnum),
+        quote!(
+            #[enumify_context]
+            struct Foo {
+                bar: u8,
+            }
+        ),
+    );
+    assert_eq!(
+        errors,
+        ["'enumify_context' attribute expects one and only one argument (the type of the context)"]
+    );
+}
+
+#[test]
+fn error_invalid_async() {
+    let errors = expect_errors(
+        quote!(BasicEnum),
+        quote!(
+            #[enumify_context(Registry)]
+            #[enumify_async]
+            struct Foo {
+                bar: u8,
+            }
+        ),
+    );
+    assert_eq!(
+        errors,
+        ["'enumify_async' cannot be combined with 'enumify_context'"]
+    );
+
+    let errors = expect_errors(
+        quote!(BasicEnum),
+        quote!(
+            #[enumify_async(true)]
+            struct Foo {
+                bar: u8,
+            }
+        ),
+    );
+    assert_eq!(errors, ["'enumify_async' attribute takes no arguments"]);
+}
+
+#[test]
+#[cfg(not(feature = "clap"))]
+fn error_clap_without_feature() {
+    let errors = expect_errors(
+        quote!(BasicEnum),
+        quote!(
+            #[enumify_clap]
+            struct Foo {
+                bar: u8,
+            }
+        ),
+    );
+    assert_eq!(
+        errors,
+        ["'enumify_clap' requires the `clap` feature of enumify_struct"]
+    );
+}
+
+#[test]
+#[cfg(not(feature = "async"))]
+fn error_async_without_feature() {
+    let errors = expect_errors(
+        quote!(BasicEnum),
+        quote!(
+            #[enumify_async]
+            struct Foo {
+                bar: u8,
+            }
+        ),
+    );
+    assert_eq!(
+        errors,
+        ["'enumify_async' requires the `async` feature of enumify_struct"]
+    );
+}
+
+#[test]
+fn error_invalid_nested() {
+    let errors = expect_errors(
+        quote!(BasicEnum),
+        quote!(
+            struct Foo {
+                #[enumify_nested]
+                #[enumify_rename(EnumifiedBar)]
+                bar: Bar,
+                #[enumify_nested(Baz)]
+                baz: Baz,
+                #[enumify_nested]
+                qux: BasicEnum,
+            }
+        ),
+    );
+    assert_eq!(
+        errors,
+        [
+            "'enumify_nested' cannot be combined with 'enumify_rename'",
+            "'enumify_nested' attribute takes no arguments",
+            "'enumify_nested' expects the target enum to wrap the nested type",
+        ]
+    );
+}
+
+#[test]
+fn error_invalid_arguments() {
+    let input = quote!(
+        struct Foo {
+            bar: u8,
+        }
+    );
+    let cases = [
+        (
+            quote!(target = BasicEnum, nmae = PartialFoo),
+            "unknown argument `nmae`, expected one of `target`, `name`, `wrap_by_default`, `derive`, `add_derive`, `remove_derive`, `vis`",
+        ),
+        (
+            quote!(target = BasicEnum, name = A, name = B),
+            "duplicate argument `name`",
+        ),
+        (
+            quote!(BasicEnum, PartialFoo, name = B),
+            "duplicate argument `name`",
+        ),
+        (
+            quote!(name = PartialFoo, BasicEnum),
+            "positional arguments must come before keyword arguments",
+        ),
+        (quote!(BasicEnum, PartialFoo, true, 1), "unexpected argument"),
+        (
+            quote!(name = PartialFoo),
+            "missing the target enum, expected `target = ...`",
+        ),
+    ];
+    for (attr, expected) in cases {
+        assert_eq!(expect_errors(attr, input.clone()), [expected]);
+    }
+}
+
+#[test]
+fn error_invalid_forwarded_attribute() {
+    let errors = expect_errors(
+        quote!(BasicEnum),
+        quote!(
+            #[enumify_attr]
+            struct Foo {
+                #[enumify_attr(= 1)]
+                bar: u8,
+            }
+        ),
+    );
+    assert_eq!(
+        errors,
+        [
+            "'enumify_attr' attribute expects the attributes to forward, e.g. `enumify_attr(serde(default))`",
+            "'enumify_attr' attribute expects the attributes to forward, e.g. `enumify_attr(serde(default))`",
+        ]
+    );
+}
+
+#[test]
+fn error_invalid_from_env() {
+    let errors = expect_errors(
+        quote!(BasicEnum),
+        quote!(
+            #[enumify_from_env]
+            enum Foo {
+                Bar(u8),
+            }
+        ),
+    );
+    assert_eq!(errors, ["'enumify_from_env' can only be used on structs"]);
+
+    let errors = expect_errors(
+        quote!(BasicEnum),
+        quote!(
+            #[enumify_from_env(prefix)]
+            struct Foo {
+                #[enumify_skip_wrap]
+                #[enumify_skip_parse]
+                bar: u8,
+            }
+        ),
+    );
+    assert_eq!(
+        errors,
+        [
+            "'enumify_from_env' attribute takes no arguments",
+            "'enumify_skip_parse' cannot be used on a field that is neither wrapped nor nested, which every layer sets",
+        ]
+    );
+}
+
+#[test]
+fn error_invalid_arguments_and_fields() {
+    let errors = expect_errors(
+        quote!(Layer, bogus = 1),
+        quote!(
+            #[enumify_context]
+            #[enumify_attr]
+            struct Foo {
+                a: &'static str,
+                b: fn(),
+            }
+        ),
+    );
+    assert_eq!(
+        errors,
+        [
+            "unknown argument `bogus`, expected one of `target`, `name`, `wrap_by_default`, `derive`, `add_derive`, `remove_derive`, `vis`",
+            "'enumify_context' attribute expects one and only one argument (the type of the context)",
+            "'enumify_attr' attribute expects the attributes to forward, e.g. `enumify_attr(serde(default))`",
+            "Using enumify_struct for a struct containing a reference is not valid.",
+            "Using enumify_struct for a struct containing a function pointer is not valid.",
+        ]
     );
 }
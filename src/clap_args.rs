@@ -0,0 +1,130 @@
+use std::{any::TypeId, error::Error, str::FromStr};
+
+use clap::{error::ErrorKind, parser::ValueSource, Arg, ArgMatches, Command};
+
+use crate::{FromBase, OptionalIntoBase};
+
+/// `clap::Args` for structures nested under a prefix, e.g. `database-` for
+/// the `--database-host` flag. The generated structure implements it, and
+/// implements `clap::Args` through it with an empty prefix.
+pub trait PrefixedArgs: Sized {
+    /// Adds the flags of the structure. The flags of fields that every layer
+    /// sets are required, unless the flags only update an existing layer.
+    fn augment_args_prefixed(
+        cmd: Command,
+        prefix: &str,
+        for_update: bool,
+    ) -> Command;
+
+    fn from_arg_matches_prefixed(
+        matches: &ArgMatches,
+        prefix: &str,
+    ) -> Result<Self, clap::Error>;
+
+    fn update_from_arg_matches_prefixed(
+        &mut self,
+        matches: &ArgMatches,
+        prefix: &str,
+    ) -> Result<(), clap::Error>;
+}
+
+/// The optional flag setting the field `name`, parsed with `FromStr`.
+/// Boolean flags may be given without a value, which sets them to `true`.
+pub fn arg<T>(prefix: &str, name: &str, help: &str) -> Arg
+where
+    T: FromStr + Clone + Send + Sync + 'static,
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    let id = format!("{prefix}{name}");
+    let mut arg = Arg::new(id.clone())
+        .long(id)
+        .required(false)
+        .value_parser(|value: &str| value.parse::<T>());
+    if !help.is_empty() {
+        arg = arg.help(help.to_owned());
+    }
+    if TypeId::of::<T>() == TypeId::of::<bool>() {
+        arg = arg.num_args(0..=1).default_missing_value("true");
+    }
+    arg
+}
+
+/// The value given to the flag added by `arg`, if any.
+pub fn get_arg<T>(matches: &ArgMatches, prefix: &str, name: &str) -> Option<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    matches.get_one::<T>(&format!("{prefix}{name}")).cloned()
+}
+
+/// The value given to the flag added by `arg`, reporting it when it is
+/// missing.
+pub fn get_required_arg<T>(
+    matches: &ArgMatches,
+    prefix: &str,
+    name: &str,
+) -> Result<T, clap::Error>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    get_arg(matches, prefix, name).ok_or_else(|| {
+        clap::Error::raw(
+            ErrorKind::MissingRequiredArgument,
+            format!("the flag `--{prefix}{name}` is required\n"),
+        )
+    })
+}
+
+/// The prefix of the flags of a structure nested in the field `name`.
+pub fn nested_arg_prefix(prefix: &str, name: &str) -> String {
+    format!("{prefix}{name}-")
+}
+
+/// Whether any flag under `prefix` was given.
+fn has_prefixed_args(matches: &ArgMatches, prefix: &str) -> bool {
+    matches.ids().any(|id| {
+        id.as_str().starts_with(prefix)
+            && matches
+                .value_source(id.as_str())
+                .is_some_and(|source| source != ValueSource::DefaultValue)
+    })
+}
+
+/// The structure nested under `prefix` if any of its flags is given, for
+/// nested structures wrapped in the target enum, which are left unset
+/// otherwise.
+pub fn get_nested_args<T: PrefixedArgs>(
+    matches: &ArgMatches,
+    prefix: &str,
+) -> Result<Option<T>, clap::Error> {
+    if !has_prefixed_args(matches, prefix) {
+        return Ok(None);
+    }
+    T::from_arg_matches_prefixed(matches, prefix).map(Some)
+}
+
+/// Updates the nested structure wrapped in `field` with the flags under
+/// `prefix`, if any of them is given. The structure is built from the flags
+/// when `field` leaves it unset.
+pub fn update_nested_args<T, E>(
+    field: &mut E,
+    matches: &ArgMatches,
+    prefix: &str,
+) -> Result<(), clap::Error>
+where
+    T: PrefixedArgs,
+    E: FromBase<T> + OptionalIntoBase<T> + Default,
+{
+    if !has_prefixed_args(matches, prefix) {
+        return Ok(());
+    }
+    let nested = match std::mem::take(field).into_optional_base() {
+        Some(mut nested) => {
+            nested.update_from_arg_matches_prefixed(matches, prefix)?;
+            nested
+        }
+        None => T::from_arg_matches_prefixed(matches, prefix)?,
+    };
+    *field = E::from_base(nested);
+    Ok(())
+}
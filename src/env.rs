@@ -0,0 +1,106 @@
+use std::{
+    cell::Cell, collections::HashMap, error::Error, hash::BuildHasher,
+    str::FromStr,
+};
+
+use crate::ResolveError;
+
+/// Builds a layer from environment variables. The structures annotated with
+/// `#[enumify_from_env]` implement it when their target enum implements
+/// `FromBase` and `Default`, the latter being the value of fields whose
+/// variable is absent, as `Layer` does.
+///
+/// Wrapped fields are parsed with `FromStr` from the variable named after the
+/// prefix and the field in upper case, e.g. `APP_PORT` for the field `port`
+/// and the prefix `APP`. Fields of structures nested with `enumify_nested`
+/// are read from variables separated from the nested field by two
+/// underscores, e.g. `APP_DATABASE__HOST`, and those wrapped in the target
+/// enum are only set when one of their variables is. Fields that are neither
+/// wrapped nor nested are set by every layer, as are those already holding
+/// the target enum, so their variable is required. Fields whose type cannot
+/// be parsed are reported, unless marked `#[enumify_skip_parse]` to leave
+/// them unset.
+pub trait FromEnv: Sized {
+    /// Reads the variables through `var`, which returns the value of the
+    /// variable it is given if it is set.
+    fn from_env_with(
+        prefix: &str,
+        var: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<Self, ResolveError>;
+
+    /// Reads the variables from the environment of the process.
+    fn from_env(prefix: &str) -> Result<Self, ResolveError> {
+        Self::from_env_with(prefix, &|name| std::env::var(name).ok())
+    }
+
+    /// Reads the variables from `vars` instead of the environment of the
+    /// process, e.g. in tests.
+    fn from_env_vars<S: BuildHasher>(
+        prefix: &str,
+        vars: &HashMap<String, String, S>,
+    ) -> Result<Self, ResolveError> {
+        Self::from_env_with(prefix, &|name| vars.get(name).cloned())
+    }
+}
+
+/// The variable holding `field` under `prefix`, e.g. `APP_PORT`.
+pub fn env_var_name(prefix: &str, field: &str) -> String {
+    if prefix.is_empty() {
+        field.to_owned()
+    } else {
+        format!("{prefix}_{field}")
+    }
+}
+
+/// The prefix of the variables read by a structure nested in `field`, e.g.
+/// `APP_DATABASE_`, so that its fields read `APP_DATABASE__HOST`.
+pub fn nested_env_prefix(prefix: &str, field: &str) -> String {
+    env_var_name(prefix, field) + "_"
+}
+
+/// Reads the structure nested under `prefix` if any of its variables is set,
+/// for nested structures wrapped in the target enum, which are left unset
+/// otherwise.
+pub fn read_nested_env<T: FromEnv>(
+    prefix: &str,
+    var: &dyn Fn(&str) -> Option<String>,
+) -> Result<Option<T>, ResolveError> {
+    let is_set = Cell::new(false);
+    let nested = T::from_env_with(prefix, &|name| {
+        let value = var(name);
+        is_set.set(is_set.get() || value.is_some());
+        value
+    });
+    if is_set.get() {
+        nested.map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Parses the variable `name` if it is set, reporting it when it is invalid.
+pub fn parse_env_var<T>(
+    var: &dyn Fn(&str) -> Option<String>,
+    name: &str,
+) -> Result<Option<T>, ResolveError>
+where
+    T: FromStr,
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    var(name)
+        .map(|value| value.parse().map_err(|e| ResolveError::new(name, e)))
+        .transpose()
+}
+
+/// Parses the variable `name`, reporting it when it is not set as well.
+pub fn parse_required_env_var<T>(
+    var: &dyn Fn(&str) -> Option<String>,
+    name: &str,
+) -> Result<T, ResolveError>
+where
+    T: FromStr,
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    parse_env_var(var, name)?
+        .ok_or_else(|| ResolveError::new(name, "the variable is not set"))
+}
@@ -4,6 +4,12 @@
 //! user-defined structure. This was developed with the goal of simplifying
 //! aggregating configurations coming from different sources, such as e.g. file,
 //! env, CLI, etc.
+//!
+//! Any enum implementing `ResolveToBase` (or one of its counterparts) can be
+//! used as the target enum, and the crate ships `Layer`, whose unset fields
+//! leave the base untouched, as well as `Ref`, whose values can be given by
+//! name and looked up in a `ReferenceRegistry`. A `LayerStack` applies
+//! layers coming from different sources in order of priority.
 
 /// The core of this crate. Call this proc macro on your structures to
 /// generate another structure containing provided Enum-wrapped fields, as
@@ -13,19 +19,124 @@
 /// default will wrap all fields in the targeted enum, unless the field
 /// already is the target enum. There are however other attributes that one
 /// can use to enforce a different behaviour:
-/// enumify_rename => rename the type in the generated structure. Useful
-/// when the nested structure itself has an enumified_struct. This enables
-/// arbitrary nesting of enumified_struct
-/// enumify_skip_wrap => this forces the macro to *not* wrap a value,
-/// e.g. `T` stays `T`. This is enabled by default if `T` is already
-/// `TargetEnum<U>`.
-/// enumify_wrap => this forces wrapping a value, e.g. `U`
-/// becomes `TargetEnum<U>`. Enabling this allows nested `TargetEnum`, e.g.
-/// `TargetEnum<V>` can become `TargetEnum<TargetEnum<V>>`
+/// - enumify_rename => rename the type in the generated structure. Useful
+///   when the nested structure itself has an enumified_struct. This
+///   enables arbitrary nesting of enumified_struct
+/// - enumify_nested => same as enumify_rename, but finds the generated
+///   structure through the `Enumify` trait, so that custom names and
+///   generic parameters do not need to be spelled out.
+/// - enumify_skip_wrap => this forces the macro to *not* wrap a value,
+///   e.g. `T` stays `T`. This is enabled by default if `T` is already
+///   `TargetEnum<U>`.
+/// - enumify_wrap => this forces wrapping a value, e.g. `U` becomes
+///   `TargetEnum<U>`. Enabling this allows nested `TargetEnum`, e.g.
+///   `TargetEnum<V>` can become `TargetEnum<TargetEnum<V>>`
+/// - enumify_skip => leaves the field out of the generated structure, so
+///   that applying it never changes the field. When the base is an enum
+///   holding another variant, the field is set to its `Default` value
+///   instead.
+/// - enumify_attr => forwards the attributes it contains to the generated
+///   structure only, e.g. `#[enumify_attr(serde(default))]` puts
+///   `#[serde(default)]` on the generated field. It can also be used on
+///   the structure itself and on enum variants.
+/// - enumify_skip_parse => leaves a wrapped or nested field unset in the
+///   layers read from the environment or the CLI, e.g. when its type does
+///   not implement `FromStr`.
+///
+/// The macro takes the target enum as first argument, optionally followed
+/// by the name of the generated structure and whether to wrap fields by
+/// default, e.g. `#[enumify_struct(Layer, PartialConfig, false)]`. The
+/// same arguments can be given by keyword, along with the derives and the
+/// visibility of the generated structure:
+/// `#[enumify_struct(target = Layer, name = PartialConfig,
+/// wrap_by_default = false, derive(Clone, Debug), vis = pub(crate))]`.
+/// The generated structure derives `Clone`, `PartialEq` and `Debug` as
+/// well as the traits derived by the original one, unless `derive(...)`
+/// replaces the former. `add_derive(...)` and `remove_derive(...)`
+/// respectively add traits to derive and remove traits from both lists,
+/// e.g. `add_derive(serde::Deserialize)` or `remove_derive(PartialEq)`.
+/// Derives are emitted in the order they are declared in, so that the
+/// expansion is reproducible.
+///
+/// Generic structures are supported: their where-clause is kept on the
+/// generated implementations, which are further bounded so that nested
+/// structures implement `Applicable` for their base. Fields mentioning a
+/// generic parameter are resolved through whichever trait the target enum
+/// implements, as other fields are; when it only does so for some types,
+/// e.g. `impl<T: Clone> ResolveToBase<T>`, the structure has to be bound
+/// accordingly, which the compiler suggests at the unresolvable field.
+///
+/// When the target enum implements `FromBase`, the generated structure
+/// also implements `From` its base, converting nested structures
+/// recursively.
+///
+/// Likewise, when it implements `Mergeable`, so does the generated
+/// structure, merging nested structures recursively. Fields that are
+/// neither wrapped nor nested are taken from the layer merged on top.
+///
+/// The `Applicable` implementation also overrides `apply_to_tracked`,
+/// recording the dotted path of every field it sets, so that
+/// `LayerStack::build_with_provenance` can tell which layer set each
+/// field.
+///
+/// Annotating a structure with `#[enumify_from_env]` makes the generated
+/// one implement `FromEnv`, for target enums implementing `FromBase` and
+/// `Default`, reading a layer from variables such as `APP_PORT` or
+/// `APP_DATABASE__HOST` with `from_env("APP")`.
+///
+/// With the `clap` feature, annotating a structure with `#[enumify_clap]`
+/// makes the generated one implement `clap::Args`, so that it can be
+/// flattened into a parser. Wrapped fields become optional flags named
+/// after them, e.g. `--log-level`, whose help is their documentation, and
+/// the flags of nested structures are prefixed with the field they are
+/// nested in, e.g. `--database-host`. Fields whose flag is absent are left
+/// unset, as are nested structures wrapped in the target enum when none of
+/// their flags is given, while the flags of fields that every layer sets,
+/// including those already holding the target enum, are required, except
+/// when they only update a layer.
+///
+/// When the generated structure derives `Serialize` or `Deserialize`,
+/// e.g. with `add_derive(serde::Deserialize)` or because the original one
+/// is seen deriving it (the macro must then come before `derive`), only
+/// the `serde` attributes renaming or skipping fields are kept, and
+/// missing wrapped fields default to the unset value of the target enum,
+/// which must implement `Default` as `Layer` does. Nested structures can
+/// be made optional with `#[enumify_attr(serde(default))]`. Otherwise, the
+/// `serde` attributes of the original structure are dropped. The `serde`
+/// feature implements the `serde` traits for `Layer` and `Ref`.
+///
+/// Annotating the structure itself with `#[enumify_context(Ctx)]` makes
+/// the generated structure implement `ApplicableWith<Ctx>` and
+/// `TryApplicableWith<Ctx>` rather than `Applicable`, for enums
+/// implementing `ResolveWithContext` or `TryResolveWithContext`. Likewise,
+/// with the `async` feature, `#[enumify_async]` makes it implement
+/// `AsyncApplicable`, for enums implementing `AsyncResolveToBase`.
+///
+/// The macro can also be used on enums, in which case the fields of every
+/// variant are wrapped. Applying such an enumified enum to a base holding
+/// the same variant applies it field-wise, while applying it to a base
+/// holding a different variant replaces the base altogether. Nested
+/// structures are then applied to their `Default` value.
 pub use enumify_macro::enumify_struct;
 
+use std::{error::Error, fmt};
+
+#[cfg(feature = "clap")]
+mod clap_args;
+mod env;
+mod reference;
+mod stack;
+
+pub use env::FromEnv;
+pub use reference::{Ref, ReferenceError, ReferenceRegistry};
+pub use stack::{LayerStack, Provenance};
+
+#[cfg(feature = "async")]
+use std::future::Future;
+
 /// In order for the generated structure to be resolved to the base structure
-/// the enum we wrap our struct in must implement this ResolveToBase trait.
+/// the enum we wrap our struct in must implement this ResolveToBase trait (or
+/// its owned counterpart, `IntoBase`).
 /// This is because the generated structure will contain fields of the enum
 /// type, and this trait tells us how to map the enum variants to their
 /// underlying base type (in the `build` function of the Applicable trait)
@@ -33,6 +144,556 @@ pub trait ResolveToBase<T> {
     fn resolve_to_base(&self) -> T;
 }
 
+/// Owned counterpart of `ResolveToBase`. Since `Applicable::apply_to`
+/// consumes the structure anyway, implementing this trait instead of
+/// `ResolveToBase` lets you move the wrapped values out of the enum rather
+/// than cloning them. An enum should implement one or the other, not both.
+pub trait IntoBase<T> {
+    fn into_base(self) -> T;
+}
+
+/// The inverse of `ResolveToBase`, wrapping a resolved value back into the
+/// enum. When the enum implements it, the generated structure implements
+/// `From<Base>`, e.g. to turn the current state back into a layer.
+pub trait FromBase<T> {
+    fn from_base(base: T) -> Self;
+}
+
+/// Merges two layers into one, e.g. to fold the layers coming from a file,
+/// the environment and the CLI before building the base once. When the
+/// enum implements it, so does the generated structure, merging it field by
+/// field.
+pub trait Mergeable {
+    /// Merges `other` on top of `self`, `other` taking precedence.
+    fn merge(self, other: Self) -> Self;
+}
+
+/// Counterpart of `IntoBase` for enums that can leave a field unset, in
+/// which case `apply_to` leaves the field of the base untouched. When an
+/// enum layer replaces the variant held by its base, unset fields get their
+/// `Default` value instead. `Layer` implements it.
+pub trait OptionalIntoBase<T> {
+    fn into_optional_base(self) -> Option<T>;
+}
+
+/// Fallible counterpart of `IntoBase`, for enums whose resolution can fail
+/// (e.g. a reference to an unknown key). Such enums are resolved by
+/// `TryApplicable::try_apply_to`, which reports the field that could not be
+/// resolved, while `Applicable::apply_to` panics on failure.
+pub trait TryResolveToBase<T> {
+    type Error: Into<Box<dyn Error + Send + Sync>>;
+
+    fn try_resolve_to_base(self) -> Result<T, Self::Error>;
+}
+
+/// Counterpart of `IntoBase` for enums that need some context to be resolved,
+/// e.g. a registry to look references up in. Structures annotated with
+/// `#[enumify_context(Ctx)]` implement `ApplicableWith<Ctx>` instead of
+/// `Applicable`, passing the context down to this trait and to nested
+/// structures.
+pub trait ResolveWithContext<T, Ctx: ?Sized> {
+    fn resolve_with_context(self, ctx: &Ctx) -> T;
+}
+
+/// Fallible counterpart of `ResolveWithContext`. Structures annotated with
+/// `#[enumify_context(Ctx)]` also implement `TryApplicableWith<Ctx>`, which
+/// reports the field that could not be resolved.
+pub trait TryResolveWithContext<T, Ctx: ?Sized> {
+    type Error: Into<Box<dyn Error + Send + Sync>>;
+
+    fn try_resolve_with_context(self, ctx: &Ctx) -> Result<T, Self::Error>;
+}
+
+/// Counterpart of `IntoBase` for enums resolved asynchronously, e.g. through
+/// a client fetching secrets. Structures annotated with `#[enumify_async]`
+/// implement `AsyncApplicable` instead of `Applicable`.
+#[cfg(feature = "async")]
+pub trait AsyncResolveToBase<T> {
+    fn resolve_to_base_async(self) -> impl Future<Output = T>;
+}
+
+/// A ready-made target enum, for layers that only set some of the fields of
+/// their base. Applying a layer leaves the fields it does not set untouched,
+/// so that e.g. `#[enumify_struct(Layer)]` is enough to stack configuration
+/// from a file, the environment and the CLI. When an enum layer replaces
+/// the variant held by its base, unset fields get their `Default` value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Layer<T> {
+    #[default]
+    Unset,
+    Set(T),
+}
+
+impl<T> Layer<T> {
+    pub fn is_set(&self) -> bool {
+        matches!(self, Layer::Set(_))
+    }
+
+    pub fn into_option(self) -> Option<T> {
+        match self {
+            Layer::Unset => None,
+            Layer::Set(value) => Some(value),
+        }
+    }
+}
+
+impl<T> OptionalIntoBase<T> for Layer<T> {
+    fn into_optional_base(self) -> Option<T> {
+        self.into_option()
+    }
+}
+
+/// `Unset` layers (de)serialize as `null`, and `Set` ones as their value.
+/// `Option` payloads are therefore not supported: `Set(None)` serializes as
+/// `null` as well, which deserializes back as `Unset`.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for Layer<T> {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match self {
+            Layer::Unset => serializer.serialize_none(),
+            Layer::Set(value) => value.serialize(serializer),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Layer<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        Option::deserialize(deserializer).map(Layer::from)
+    }
+}
+
+impl<T> From<Option<T>> for Layer<T> {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Layer::Unset, Layer::Set)
+    }
+}
+
+impl<T> FromBase<T> for Layer<T> {
+    fn from_base(base: T) -> Self {
+        Layer::Set(base)
+    }
+}
+
+impl<T> Mergeable for Layer<T> {
+    fn merge(self, other: Self) -> Self {
+        match other {
+            Layer::Unset => self,
+            other => other,
+        }
+    }
+}
+
+/// The error returned by `TryApplicable`, carrying the dotted path of the
+/// field that could not be resolved (e.g. `b.a_prime`) along with the error
+/// returned by its `TryResolveToBase` implementation. `FromEnv` returns it as
+/// well, with the name of the variable that could not be parsed as path.
+#[derive(Debug)]
+pub struct ResolveError {
+    path: String,
+    source: Box<dyn Error + Send + Sync>,
+}
+
+impl ResolveError {
+    pub fn new(
+        field: impl Into<String>,
+        source: impl Into<Box<dyn Error + Send + Sync>>,
+    ) -> Self {
+        ResolveError {
+            path: field.into(),
+            source: source.into(),
+        }
+    }
+
+    /// Prefixes the path of the failing field with the field of the parent
+    /// structure it is nested in.
+    pub fn within(mut self, parent: &str) -> Self {
+        self.path = format!("{parent}.{}", self.path);
+        self
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn into_source(self) -> Box<dyn Error + Send + Sync> {
+        self.source
+    }
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to resolve `{}`: {}", self.path, self.source)
+    }
+}
+
+impl Error for ResolveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.source)
+    }
+}
+
+/// Expands to `head bounds body`, given `{ head } [bounds] body` followed by
+/// `#[cfg(..)] [bounds]` for each conditionally compiled field, whose bounds
+/// are only added when the `cfg` holds. Attributes on where predicates are
+/// unstable, while those on macro invocations are not.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __enumify_cfg_bounds {
+    ({ $($head:tt)* } [$($bounds:tt)*] $body:tt) => {
+        $($head)* $($bounds)* $body
+    };
+    (
+        { $($head:tt)* } [$($bounds:tt)*] $body:tt
+        #[cfg($($cfg:tt)*)] [$($cfg_bounds:tt)*]
+        $($rest:tt)*
+    ) => {
+        #[cfg($($cfg)*)]
+        $crate::__private::cfg_bounds! {
+            { $($head)* } [$($bounds)* $($cfg_bounds)*] $body $($rest)*
+        }
+        #[cfg(not($($cfg)*))]
+        $crate::__private::cfg_bounds! {
+            { $($head)* } [$($bounds)*] $body $($rest)*
+        }
+    };
+}
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "async")]
+    use super::{AsyncResolveToBase, Future};
+    use super::{
+        Error, IntoBase, OptionalIntoBase, ResolveToBase, ResolveWithContext,
+        TryResolveToBase, TryResolveWithContext,
+    };
+
+    // A blanket `IntoBase` implementation for every `ResolveToBase` would
+    // prevent users from implementing `IntoBase` on their own generic enums
+    // (downstream crates could implement `ResolveToBase` for them as well),
+    // so the generated code instead goes through this trait, implemented for
+    // both. The marker keeps the two implementations from overlapping and is
+    // always inferred. Resolving to `None` leaves the field untouched.
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` cannot resolve fields of type `{T}`",
+        label = "the target enum implements none of `ResolveToBase<{T}>`, `IntoBase<{T}>`, `TryResolveToBase<{T}>` or `OptionalIntoBase<{T}>` for this field",
+        note = "when the target enum only implements it for some types, e.g. `impl<T: Clone> ResolveToBase<T>`, bound the generic parameters of the structure accordingly, e.g. `struct Foo<T: Clone>`"
+    )]
+    pub trait Resolve<T, Marker> {
+        fn resolve(self) -> Option<T>;
+
+        /// Resolves a field of a variant replacing the base, which has to be
+        /// given a value even when left unset.
+        fn resolve_or_unset(self) -> T
+        where
+            Self: Sized,
+            Marker: Unset<T>,
+        {
+            self.resolve().unwrap_or_else(Marker::unset)
+        }
+    }
+
+    pub struct ByRef;
+    pub struct ByValue;
+    pub struct ByTry;
+    pub struct ByContext;
+    pub struct ByTryContext;
+    #[cfg(feature = "async")]
+    pub struct ByAsync;
+    pub struct ByOptional;
+
+    #[cfg(feature = "clap")]
+    pub use crate::clap_args::{
+        arg, get_arg, get_nested_args, get_required_arg, nested_arg_prefix,
+        update_nested_args, PrefixedArgs,
+    };
+    pub use crate::{
+        __enumify_cfg_bounds as cfg_bounds,
+        env::{
+            env_var_name, nested_env_prefix, parse_env_var,
+            parse_required_env_var, read_nested_env,
+        },
+        stack::field_path,
+    };
+    #[cfg(feature = "clap")]
+    pub use clap;
+    #[cfg(feature = "async")]
+    pub use futures;
+
+    /// The value of a field its layer leaves unset, for the markers of the
+    /// enums that can do so.
+    pub trait Unset<T> {
+        fn unset() -> T;
+    }
+
+    macro_rules! always_set {
+        ($($marker:ty),*) => {
+            $(
+                impl<T> Unset<T> for $marker {
+                    fn unset() -> T {
+                        unreachable!("the field is always set")
+                    }
+                }
+            )*
+        };
+    }
+
+    always_set!(ByRef, ByValue, ByTry, ByContext, ByTryContext);
+    #[cfg(feature = "async")]
+    always_set!(ByAsync);
+
+    impl<T: Default> Unset<T> for ByOptional {
+        fn unset() -> T {
+            T::default()
+        }
+    }
+
+    impl<T, E: ResolveToBase<T>> Resolve<T, ByRef> for E {
+        fn resolve(self) -> Option<T> {
+            Some(self.resolve_to_base())
+        }
+    }
+
+    impl<T, E: IntoBase<T>> Resolve<T, ByValue> for E {
+        fn resolve(self) -> Option<T> {
+            Some(self.into_base())
+        }
+    }
+
+    impl<T, E: TryResolveToBase<T>> Resolve<T, ByTry> for E {
+        fn resolve(self) -> Option<T> {
+            match self.try_resolve_to_base() {
+                Ok(value) => Some(value),
+                Err(e) => panic!("failed to resolve field: {}", e.into()),
+            }
+        }
+    }
+
+    impl<T, E: OptionalIntoBase<T>> Resolve<T, ByOptional> for E {
+        fn resolve(self) -> Option<T> {
+            self.into_optional_base()
+        }
+    }
+
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` cannot resolve fields of type `{T}`",
+        label = "the target enum implements none of `ResolveToBase<{T}>`, `IntoBase<{T}>`, `TryResolveToBase<{T}>` or `OptionalIntoBase<{T}>` for this field",
+        note = "when the target enum only implements it for some types, e.g. `impl<T: Clone> ResolveToBase<T>`, bound the generic parameters of the structure accordingly, e.g. `struct Foo<T: Clone>`"
+    )]
+    pub trait TryResolve<T, Marker> {
+        fn try_resolve(self)
+            -> Result<Option<T>, Box<dyn Error + Send + Sync>>;
+
+        /// Counterpart of `Resolve::resolve_or_unset`.
+        fn try_resolve_or_unset(self) -> Result<T, Box<dyn Error + Send + Sync>>
+        where
+            Self: Sized,
+            Marker: Unset<T>,
+        {
+            Ok(self.try_resolve()?.unwrap_or_else(Marker::unset))
+        }
+    }
+
+    impl<T, E: Resolve<T, ByRef>> TryResolve<T, ByRef> for E {
+        fn try_resolve(
+            self,
+        ) -> Result<Option<T>, Box<dyn Error + Send + Sync>> {
+            Ok(self.resolve())
+        }
+    }
+
+    impl<T, E: Resolve<T, ByValue>> TryResolve<T, ByValue> for E {
+        fn try_resolve(
+            self,
+        ) -> Result<Option<T>, Box<dyn Error + Send + Sync>> {
+            Ok(self.resolve())
+        }
+    }
+
+    impl<T, E: TryResolveToBase<T>> TryResolve<T, ByTry> for E {
+        fn try_resolve(
+            self,
+        ) -> Result<Option<T>, Box<dyn Error + Send + Sync>> {
+            self.try_resolve_to_base().map(Some).map_err(Into::into)
+        }
+    }
+
+    impl<T, E: Resolve<T, ByOptional>> TryResolve<T, ByOptional> for E {
+        fn try_resolve(
+            self,
+        ) -> Result<Option<T>, Box<dyn Error + Send + Sync>> {
+            Ok(self.resolve())
+        }
+    }
+
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` cannot resolve fields of type `{T}`",
+        label = "the target enum implements none of `ResolveToBase<{T}>`, `IntoBase<{T}>`, `TryResolveToBase<{T}>`, `OptionalIntoBase<{T}>`, `ResolveWithContext<{T}, {Ctx}>` or `TryResolveWithContext<{T}, {Ctx}>` for this field",
+        note = "when the target enum only implements it for some types, e.g. `impl<T: Clone> ResolveToBase<T>`, bound the generic parameters of the structure accordingly, e.g. `struct Foo<T: Clone>`"
+    )]
+    pub trait ResolveWith<T, Ctx: ?Sized, Marker> {
+        fn resolve_with(self, ctx: &Ctx) -> Option<T>;
+
+        /// Counterpart of `Resolve::resolve_or_unset`.
+        fn resolve_with_or_unset(self, ctx: &Ctx) -> T
+        where
+            Self: Sized,
+            Marker: Unset<T>,
+        {
+            self.resolve_with(ctx).unwrap_or_else(Marker::unset)
+        }
+    }
+
+    macro_rules! resolve_without_context {
+        ($($marker:ty),*) => {
+            $(
+                impl<T, Ctx: ?Sized, E: Resolve<T, $marker>>
+                    ResolveWith<T, Ctx, $marker> for E
+                {
+                    fn resolve_with(self, _ctx: &Ctx) -> Option<T> {
+                        self.resolve()
+                    }
+                }
+            )*
+        };
+    }
+
+    resolve_without_context!(ByRef, ByValue, ByTry, ByOptional);
+
+    impl<T, Ctx: ?Sized, E: ResolveWithContext<T, Ctx>>
+        ResolveWith<T, Ctx, ByContext> for E
+    {
+        fn resolve_with(self, ctx: &Ctx) -> Option<T> {
+            Some(self.resolve_with_context(ctx))
+        }
+    }
+
+    impl<T, Ctx: ?Sized, E: TryResolveWithContext<T, Ctx>>
+        ResolveWith<T, Ctx, ByTryContext> for E
+    {
+        fn resolve_with(self, ctx: &Ctx) -> Option<T> {
+            match self.try_resolve_with_context(ctx) {
+                Ok(value) => Some(value),
+                Err(e) => panic!("failed to resolve field: {}", e.into()),
+            }
+        }
+    }
+
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` cannot resolve fields of type `{T}`",
+        label = "the target enum implements none of `ResolveToBase<{T}>`, `IntoBase<{T}>`, `TryResolveToBase<{T}>`, `OptionalIntoBase<{T}>`, `ResolveWithContext<{T}, {Ctx}>` or `TryResolveWithContext<{T}, {Ctx}>` for this field",
+        note = "when the target enum only implements it for some types, e.g. `impl<T: Clone> ResolveToBase<T>`, bound the generic parameters of the structure accordingly, e.g. `struct Foo<T: Clone>`"
+    )]
+    pub trait TryResolveWith<T, Ctx: ?Sized, Marker> {
+        fn try_resolve_with(
+            self,
+            ctx: &Ctx,
+        ) -> Result<Option<T>, Box<dyn Error + Send + Sync>>;
+
+        /// Counterpart of `Resolve::resolve_or_unset`.
+        fn try_resolve_with_or_unset(
+            self,
+            ctx: &Ctx,
+        ) -> Result<T, Box<dyn Error + Send + Sync>>
+        where
+            Self: Sized,
+            Marker: Unset<T>,
+        {
+            Ok(self.try_resolve_with(ctx)?.unwrap_or_else(Marker::unset))
+        }
+    }
+
+    macro_rules! try_resolve_without_context {
+        ($($marker:ty),*) => {
+            $(
+                impl<T, Ctx: ?Sized, E: TryResolve<T, $marker>>
+                    TryResolveWith<T, Ctx, $marker> for E
+                {
+                    fn try_resolve_with(
+                        self,
+                        _ctx: &Ctx,
+                    ) -> Result<Option<T>, Box<dyn Error + Send + Sync>> {
+                        self.try_resolve()
+                    }
+                }
+            )*
+        };
+    }
+
+    try_resolve_without_context!(ByRef, ByValue, ByTry, ByOptional);
+
+    impl<T, Ctx: ?Sized, E: ResolveWithContext<T, Ctx>>
+        TryResolveWith<T, Ctx, ByContext> for E
+    {
+        fn try_resolve_with(
+            self,
+            ctx: &Ctx,
+        ) -> Result<Option<T>, Box<dyn Error + Send + Sync>> {
+            Ok(Some(self.resolve_with_context(ctx)))
+        }
+    }
+
+    impl<T, Ctx: ?Sized, E: TryResolveWithContext<T, Ctx>>
+        TryResolveWith<T, Ctx, ByTryContext> for E
+    {
+        fn try_resolve_with(
+            self,
+            ctx: &Ctx,
+        ) -> Result<Option<T>, Box<dyn Error + Send + Sync>> {
+            self.try_resolve_with_context(ctx)
+                .map(Some)
+                .map_err(Into::into)
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` cannot resolve fields of type `{T}`",
+        label = "the target enum implements none of `ResolveToBase<{T}>`, `IntoBase<{T}>`, `TryResolveToBase<{T}>`, `OptionalIntoBase<{T}>` or `AsyncResolveToBase<{T}>` for this field",
+        note = "when the target enum only implements it for some types, e.g. `impl<T: Clone> ResolveToBase<T>`, bound the generic parameters of the structure accordingly, e.g. `struct Foo<T: Clone>`"
+    )]
+    pub trait ResolveAsync<T, Marker> {
+        fn resolve_async(self) -> impl Future<Output = Option<T>>;
+
+        /// Counterpart of `Resolve::resolve_or_unset`.
+        fn resolve_async_or_unset(self) -> impl Future<Output = T>
+        where
+            Self: Sized,
+            Marker: Unset<T>,
+        {
+            async move { self.resolve_async().await.unwrap_or_else(Marker::unset) }
+        }
+    }
+
+    #[cfg(feature = "async")]
+    macro_rules! resolve_synchronously {
+        ($($marker:ty),*) => {
+            $(
+                impl<T, E: Resolve<T, $marker>> ResolveAsync<T, $marker> for E {
+                    fn resolve_async(self) -> impl Future<Output = Option<T>> {
+                        std::future::ready(self.resolve())
+                    }
+                }
+            )*
+        };
+    }
+
+    #[cfg(feature = "async")]
+    resolve_synchronously!(ByRef, ByValue, ByTry, ByOptional);
+
+    #[cfg(feature = "async")]
+    impl<T, E: AsyncResolveToBase<T>> ResolveAsync<T, ByAsync> for E {
+        async fn resolve_async(self) -> Option<T> {
+            Some(self.resolve_to_base_async().await)
+        }
+    }
+}
+
 /// The trait is implemented for every generated structure. Thanks to this, you
 /// can use enumify_struct in generic contexts.
 pub trait Applicable: Sized {
@@ -50,4 +711,125 @@ pub trait Applicable: Sized {
 
     /// Similar to `Applicable::build`, but takes the Base by reference.
     fn apply_to(self, base: &mut Self::Base);
+
+    /// Similar to `Applicable::apply_to`, but calls `record` with the dotted
+    /// path of every field it sets, prefixed with `path` unless empty (e.g.
+    /// `database.port`). Generated structures implement it, recursing into
+    /// nested structures, while other implementations record nothing unless
+    /// they override it. Generated enums replacing the variant of their base
+    /// record `path` itself before the fields of the new variant.
+    /// `LayerStack` relies on it to build a `Provenance`.
+    fn apply_to_tracked(
+        self,
+        base: &mut Self::Base,
+        path: &str,
+        record: &mut dyn FnMut(&str),
+    ) {
+        let _ = (path, record);
+        self.apply_to(base);
+    }
+
+    /// Turns a base back into the generated structure, with every field set.
+    /// Nested structures are converted recursively.
+    fn from_base(base: Self::Base) -> Self
+    where
+        Self: From<Self::Base>,
+    {
+        Self::from(base)
+    }
+}
+
+/// Implemented for every structure the macro is used on, naming the
+/// structure it generates. Fields marked `enumify_nested` rely on it to find
+/// the type to use, rather than spelling it with `enumify_rename`.
+pub trait Enumify {
+    type Enumified;
+}
+
+/// Counterpart of `Applicable` implemented instead of it by the structures
+/// annotated with `#[enumify_context(Ctx)]`, resolving their fields through
+/// `ResolveWithContext` with the given context. Nested structures must use
+/// the same context.
+pub trait ApplicableWith<Ctx: ?Sized>: Sized {
+    type Base;
+
+    /// Similar to `Applicable::build`, with a context to resolve fields.
+    fn build_with(self, mut base: Self::Base, ctx: &Ctx) -> Self::Base {
+        self.apply_to_with(&mut base, ctx);
+        base
+    }
+
+    /// Similar to `Applicable::apply_to`, with a context to resolve fields.
+    /// Panics when a field resolved through `TryResolveWithContext` fails to
+    /// resolve, e.g. an unknown `Ref`, which `try_apply_to_with` reports
+    /// instead.
+    fn apply_to_with(self, base: &mut Self::Base, ctx: &Ctx);
+}
+
+/// Fallible counterpart of `ApplicableWith`, also implemented by the
+/// structures annotated with `#[enumify_context(Ctx)]`. Fields are resolved
+/// through `TryResolveWithContext` where the enum implements it.
+pub trait TryApplicableWith<Ctx: ?Sized>: ApplicableWith<Ctx> {
+    /// Similar to `TryApplicable::try_build`, with a context to resolve
+    /// fields.
+    fn try_build_with(
+        self,
+        mut base: Self::Base,
+        ctx: &Ctx,
+    ) -> Result<Self::Base, ResolveError> {
+        self.try_apply_to_with(&mut base, ctx)?;
+        Ok(base)
+    }
+
+    /// Similar to `TryApplicable::try_apply_to`, with a context to resolve
+    /// fields.
+    fn try_apply_to_with(
+        self,
+        base: &mut Self::Base,
+        ctx: &Ctx,
+    ) -> Result<(), ResolveError>;
+}
+
+/// Counterpart of `Applicable` implemented instead of it by the structures
+/// annotated with `#[enumify_async]`, resolving their fields through
+/// `AsyncResolveToBase`. The fields of a structure are resolved concurrently,
+/// nested structures included.
+#[cfg(feature = "async")]
+pub trait AsyncApplicable: Sized {
+    type Base;
+
+    /// Similar to `Applicable::build`, resolving fields asynchronously.
+    fn build_async(
+        self,
+        mut base: Self::Base,
+    ) -> impl Future<Output = Self::Base> {
+        async move {
+            self.apply_to_async(&mut base).await;
+            base
+        }
+    }
+
+    /// Similar to `Applicable::apply_to`, resolving fields asynchronously.
+    fn apply_to_async(self, base: &mut Self::Base) -> impl Future<Output = ()>;
+}
+
+/// Fallible counterpart of `Applicable`, also implemented for every generated
+/// structure. Fields are resolved through `TryResolveToBase` where the enum
+/// implements it, and the first failure is returned along with the path of
+/// the field that caused it.
+pub trait TryApplicable: Applicable {
+    /// Similar to `Applicable::build`, but stops at the first field that
+    /// cannot be resolved.
+    fn try_build(
+        self,
+        mut base: Self::Base,
+    ) -> Result<Self::Base, ResolveError> {
+        self.try_apply_to(&mut base)?;
+        Ok(base)
+    }
+
+    /// Similar to `TryApplicable::try_build`, but takes the Base by
+    /// reference. Note that the fields preceding the failing one have then
+    /// already been applied.
+    fn try_apply_to(self, base: &mut Self::Base) -> Result<(), ResolveError>;
 }
@@ -0,0 +1,143 @@
+use std::{
+    collections::HashMap, error::Error, fmt, hash::BuildHasher, str::FromStr,
+};
+
+use crate::{FromBase, Mergeable, TryResolveWithContext};
+
+/// A ready-made target enum for values that may be given by name, e.g. to
+/// share a secret between several fields. References are looked up in a
+/// `ReferenceRegistry` passed as context, so structures using it are
+/// annotated with `#[enumify_context(Registry)]`. With the `serde` feature,
+/// it (de)serializes as e.g. `{ "value": 8080 }` or `{ "reference": "port" }`.
+///
+/// Such structures implement `ApplicableWith` rather than `Applicable`, so
+/// they cannot be pushed onto a `LayerStack`. Layers coming from different
+/// sources are instead merged with `Mergeable`, and the result is applied
+/// once with `TryApplicableWith::try_apply_to_with`, which reports unknown
+/// references where `ApplicableWith::apply_to_with` panics.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Ref<T> {
+    Value(T),
+    Reference(String),
+}
+
+impl<T> FromBase<T> for Ref<T> {
+    fn from_base(base: T) -> Self {
+        Ref::Value(base)
+    }
+}
+
+impl<T> Mergeable for Ref<T> {
+    /// A `Ref` is always set, so `other` always takes precedence.
+    fn merge(self, other: Self) -> Self {
+        other
+    }
+}
+
+impl<T, R: ReferenceRegistry<T> + ?Sized> TryResolveWithContext<T, R>
+    for Ref<T>
+{
+    type Error = ReferenceError;
+
+    /// Follows references until reaching a value, failing if a reference
+    /// is met twice.
+    fn try_resolve_with_context(self, registry: &R) -> Result<T, Self::Error> {
+        let mut chain: Vec<String> = Vec::new();
+        let mut current = self;
+        loop {
+            match current {
+                Ref::Value(value) => return Ok(value),
+                Ref::Reference(name) => {
+                    let is_cycle = chain.contains(&name);
+                    chain.push(name);
+                    if is_cycle {
+                        return Err(ReferenceError::Cycle(chain));
+                    }
+                    current = registry.lookup(&chain[chain.len() - 1])?;
+                }
+            }
+        }
+    }
+}
+
+/// Resolves named references to values of type `T`, or to other references
+/// which are then followed in turn. It is implemented for maps from names to
+/// strings, which are parsed into the type of the field referring to them,
+/// and for maps from names to `Ref`s, which may alias one another.
+pub trait ReferenceRegistry<T> {
+    fn lookup(&self, name: &str) -> Result<Ref<T>, ReferenceError>;
+}
+
+impl<T, S> ReferenceRegistry<T> for HashMap<String, String, S>
+where
+    T: FromStr,
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+    S: BuildHasher,
+{
+    fn lookup(&self, name: &str) -> Result<Ref<T>, ReferenceError> {
+        let value = self
+            .get(name)
+            .ok_or_else(|| ReferenceError::Unknown(name.to_string()))?;
+        value.parse().map(Ref::Value).map_err(|e: T::Err| {
+            ReferenceError::Invalid {
+                name: name.to_string(),
+                source: e.into(),
+            }
+        })
+    }
+}
+
+impl<T: Clone, S: BuildHasher> ReferenceRegistry<T>
+    for HashMap<String, Ref<T>, S>
+{
+    fn lookup(&self, name: &str) -> Result<Ref<T>, ReferenceError> {
+        self.get(name)
+            .cloned()
+            .ok_or_else(|| ReferenceError::Unknown(name.to_string()))
+    }
+}
+
+/// The error returned when a reference cannot be resolved.
+#[derive(Debug)]
+pub enum ReferenceError {
+    /// No value has the given name.
+    Unknown(String),
+    /// The value with the given name is not valid for the referring field.
+    Invalid {
+        name: String,
+        source: Box<dyn Error + Send + Sync>,
+    },
+    /// The references followed, the last one repeating an earlier one in
+    /// the chain.
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for ReferenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReferenceError::Unknown(name) => {
+                write!(f, "unknown reference `{name}`")
+            }
+            ReferenceError::Invalid { name, source } => {
+                write!(f, "invalid value for reference `{name}`: {source}")
+            }
+            ReferenceError::Cycle(chain) => {
+                write!(f, "reference cycle: `{}`", chain.join("` -> `"))
+            }
+        }
+    }
+}
+
+impl Error for ReferenceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReferenceError::Unknown(_) | ReferenceError::Cycle(_) => None,
+            ReferenceError::Invalid { source, .. } => Some(&**source),
+        }
+    }
+}
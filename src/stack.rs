@@ -0,0 +1,224 @@
+use std::collections::{btree_map, BTreeMap};
+
+use crate::Applicable;
+
+/// Layers of any `Applicable` type sharing the same base, e.g. the layers
+/// read from a file, the environment and the CLI, applied in order of
+/// priority to build the base. Layers of higher priority are applied last,
+/// and so take precedence; layers of equal priority are applied in the order
+/// they were pushed in.
+pub struct LayerStack<B> {
+    layers: Vec<NamedLayer<B>>,
+}
+
+struct NamedLayer<B> {
+    name: String,
+    priority: i32,
+    layer: Box<dyn StackedLayer<B>>,
+}
+
+/// `Applicable` taking itself boxed, so that layers of different types can
+/// be stacked together.
+trait StackedLayer<B> {
+    fn apply_boxed(self: Box<Self>, base: &mut B);
+
+    fn apply_boxed_tracked(
+        self: Box<Self>,
+        base: &mut B,
+        record: &mut dyn FnMut(&str),
+    );
+}
+
+impl<A: Applicable> StackedLayer<A::Base> for A {
+    fn apply_boxed(self: Box<Self>, base: &mut A::Base) {
+        self.apply_to(base);
+    }
+
+    fn apply_boxed_tracked(
+        self: Box<Self>,
+        base: &mut A::Base,
+        record: &mut dyn FnMut(&str),
+    ) {
+        self.apply_to_tracked(base, "", record);
+    }
+}
+
+impl<B> LayerStack<B> {
+    pub fn new() -> Self {
+        LayerStack { layers: Vec::new() }
+    }
+
+    /// Adds `layer` under `name`, to be applied after the layers of lower
+    /// or equal priority.
+    pub fn push<A>(
+        &mut self,
+        name: impl Into<String>,
+        priority: i32,
+        layer: A,
+    ) -> &mut Self
+    where
+        A: Applicable<Base = B> + 'static,
+    {
+        // Keeps the layers sorted, after those of equal priority
+        let index = self.layers.partition_point(|l| l.priority <= priority);
+        self.layers.insert(
+            index,
+            NamedLayer {
+                name: name.into(),
+                priority,
+                layer: Box::new(layer),
+            },
+        );
+        self
+    }
+
+    /// Similar to `LayerStack::push`, taking the stack by value.
+    pub fn with_layer<A>(
+        mut self,
+        name: impl Into<String>,
+        priority: i32,
+        layer: A,
+    ) -> Self
+    where
+        A: Applicable<Base = B> + 'static,
+    {
+        self.push(name, priority, layer);
+        self
+    }
+
+    /// The names of the layers, in the order they are applied in.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.layers.iter().map(|l| l.name.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Applies every layer to `base`, in order of priority.
+    pub fn apply_to(self, base: &mut B) {
+        for named in self.layers {
+            named.layer.apply_boxed(base);
+        }
+    }
+
+    /// Similar to `LayerStack::apply_to`, but takes the base by value.
+    pub fn build_from(self, mut base: B) -> B {
+        self.apply_to(&mut base);
+        base
+    }
+
+    /// Similar to `LayerStack::apply_to`, also returning the name of the
+    /// layer that last set each field.
+    pub fn apply_to_with_provenance(self, base: &mut B) -> Provenance {
+        let mut provenance = Provenance::default();
+        for named in self.layers {
+            let name = named.name;
+            named.layer.apply_boxed_tracked(base, &mut |path| {
+                provenance.record(path, &name);
+            });
+        }
+        provenance
+    }
+
+    /// Similar to `LayerStack::apply_to_with_provenance`, but takes the base
+    /// by value.
+    pub fn build_from_with_provenance(self, mut base: B) -> (B, Provenance) {
+        let provenance = self.apply_to_with_provenance(&mut base);
+        (base, provenance)
+    }
+}
+
+impl<B: Default> LayerStack<B> {
+    /// Applies every layer to the `Default` base, in order of priority.
+    pub fn build(self) -> B {
+        self.build_from(B::default())
+    }
+
+    /// Similar to `LayerStack::build`, also returning the name of the layer
+    /// that last set each field.
+    pub fn build_with_provenance(self) -> (B, Provenance) {
+        self.build_from_with_provenance(B::default())
+    }
+}
+
+impl<B> Default for LayerStack<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The name of the layer that last set each field of a base built by a
+/// `LayerStack`, by dotted path, e.g. `database.port`. Fields no layer set
+/// are absent, as are the fields set by layers that do not record them (see
+/// `Applicable::apply_to_tracked`). A layer replacing the variant of an enum
+/// sets the enum itself, e.g. `output`, and the fields of the previous
+/// variant are forgotten. The path of an enum at the root is empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Provenance {
+    layers: BTreeMap<String, String>,
+}
+
+impl Provenance {
+    /// Records that the layer `name` set the field at `path`, forgetting the
+    /// fields within it, which were set along with a value it replaced.
+    fn record(&mut self, path: &str, name: &str) {
+        if path.is_empty() {
+            self.layers.clear();
+        } else {
+            let prefix = format!("{path}.");
+            let within = self
+                .layers
+                .range(prefix.clone()..)
+                .map(|(p, _)| p)
+                .take_while(|p| p.starts_with(&prefix))
+                .cloned()
+                .collect::<Vec<_>>();
+            for p in within {
+                self.layers.remove(&p);
+            }
+        }
+        self.layers.insert(path.to_owned(), name.to_owned());
+    }
+
+    /// The name of the layer that last set the field at `path`.
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.layers.get(path).map(String::as_str)
+    }
+
+    /// The paths of the fields that were set along with the name of the layer
+    /// that last set them, sorted by path.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.layers.iter().map(|(p, l)| (p.as_str(), l.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+}
+
+impl IntoIterator for Provenance {
+    type Item = (String, String);
+    type IntoIter = btree_map::IntoIter<String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.layers.into_iter()
+    }
+}
+
+/// The dotted path of `field` within the structure at `path`.
+pub fn field_path(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_owned()
+    } else {
+        format!("{path}.{field}")
+    }
+}
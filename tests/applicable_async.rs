@@ -0,0 +1,150 @@
+#![cfg(feature = "async")]
+
+use std::{
+    cell::Cell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use enumify_struct::{enumify_struct, AsyncApplicable, AsyncResolveToBase};
+use futures::executor::block_on;
+
+thread_local! {
+    static IN_FLIGHT: Cell<usize> = const { Cell::new(0) };
+    static MAX_IN_FLIGHT: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Stands in for a remote client: the lookup is pending once before
+/// completing, which lets concurrent lookups overlap.
+struct MockLookup {
+    key: String,
+    started: bool,
+}
+
+impl Future for MockLookup {
+    type Output = String;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<String> {
+        if !self.started {
+            self.started = true;
+            let in_flight = IN_FLIGHT.get() + 1;
+            IN_FLIGHT.set(in_flight);
+            MAX_IN_FLIGHT.set(MAX_IN_FLIGHT.get().max(in_flight));
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        IN_FLIGHT.set(IN_FLIGHT.get() - 1);
+        Poll::Ready(format!("secret:{}", self.key))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Fetched<T> {
+    Value(T),
+    Secret(String),
+}
+
+impl<T: From<String>> AsyncResolveToBase<T> for Fetched<T> {
+    async fn resolve_to_base_async(self) -> T {
+        match self {
+            Fetched::Value(v) => v,
+            Fetched::Secret(key) => MockLookup {
+                key,
+                started: false,
+            }
+            .await
+            .into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Fetched)]
+#[enumify_async]
+struct Credentials {
+    username: String,
+    password: String,
+    #[cfg(any())]
+    token: String,
+    #[enumify_skip]
+    session: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Fetched)]
+#[enumify_async]
+struct Config {
+    name: String,
+    #[enumify_rename(EnumifiedCredentials)]
+    credentials: Credentials,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[enumify_struct(Fetched)]
+#[enumify_async]
+enum Storage {
+    Local(String),
+    Remote {
+        url: String,
+        #[enumify_rename(EnumifiedCredentials)]
+        credentials: Credentials,
+    },
+}
+
+#[test]
+fn test_fields_are_resolved_concurrently() {
+    let config = EnumifiedConfig {
+        name: Fetched::Value("app".to_string()),
+        credentials: EnumifiedCredentials {
+            username: Fetched::Secret("username".to_string()),
+            password: Fetched::Secret("password".to_string()),
+        },
+    };
+
+    let base = block_on(config.build_async(Config::default()));
+    assert_eq!(
+        base,
+        Config {
+            name: "app".to_string(),
+            credentials: Credentials {
+                username: "secret:username".to_string(),
+                password: "secret:password".to_string(),
+                session: None,
+            },
+        }
+    );
+    assert_eq!(MAX_IN_FLIGHT.get(), 2);
+}
+
+#[test]
+fn test_enum_is_resolved_asynchronously() {
+    let mut base = Storage::Remote {
+        url: "https://example.com".to_string(),
+        credentials: Credentials::default(),
+    };
+
+    let layer = EnumifiedStorage::Remote {
+        url: Fetched::Value("https://example.org".to_string()),
+        credentials: EnumifiedCredentials {
+            username: Fetched::Value("user".to_string()),
+            password: Fetched::Secret("password".to_string()),
+        },
+    };
+    block_on(layer.apply_to_async(&mut base));
+    assert_eq!(
+        base,
+        Storage::Remote {
+            url: "https://example.org".to_string(),
+            credentials: Credentials {
+                username: "user".to_string(),
+                password: "secret:password".to_string(),
+                session: None,
+            },
+        }
+    );
+
+    let layer = EnumifiedStorage::Local(Fetched::Secret("path".to_string()));
+    block_on(layer.apply_to_async(&mut base));
+    assert_eq!(base, Storage::Local("secret:path".to_string()));
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use enumify_struct::{enumify_struct, ApplicableWith, ResolveWithContext};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Referenced<T> {
+    Value(T),
+    Reference(String),
+}
+
+struct Registry {
+    values: HashMap<String, String>,
+}
+
+impl<T: std::str::FromStr + Default> ResolveWithContext<T, Registry>
+    for Referenced<T>
+{
+    fn resolve_with_context(self, registry: &Registry) -> T {
+        match self {
+            Referenced::Value(v) => v,
+            Referenced::Reference(r) => registry
+                .values
+                .get(&r)
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Referenced)]
+#[enumify_context(Registry)]
+struct Database {
+    url: String,
+    pool_size: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Referenced)]
+#[enumify_context(Registry)]
+struct Config {
+    name: String,
+    #[enumify_rename(EnumifiedDatabase)]
+    database: Database,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[enumify_struct(Referenced)]
+#[enumify_context(Registry)]
+enum Backend {
+    Memory,
+    Remote {
+        host: String,
+        #[enumify_rename(EnumifiedDatabase)]
+        database: Database,
+    },
+}
+
+fn registry() -> Registry {
+    Registry {
+        values: HashMap::from([
+            ("db_url".to_string(), "postgres://db".to_string()),
+            ("db_pool".to_string(), "8".to_string()),
+            ("host".to_string(), "example.com".to_string()),
+        ]),
+    }
+}
+
+#[test]
+fn test_context_is_threaded_to_nested_structs() {
+    let config = EnumifiedConfig {
+        name: Referenced::Value("app".to_string()),
+        database: EnumifiedDatabase {
+            url: Referenced::Reference("db_url".to_string()),
+            pool_size: Referenced::Reference("db_pool".to_string()),
+        },
+    };
+
+    let base = config.build_with(Config::default(), &registry());
+    assert_eq!(
+        base,
+        Config {
+            name: "app".to_string(),
+            database: Database {
+                url: "postgres://db".to_string(),
+                pool_size: 8,
+            },
+        }
+    );
+}
+
+#[test]
+fn test_context_in_enum_replacement() {
+    let backend = EnumifiedBackend::Remote {
+        host: Referenced::Reference("host".to_string()),
+        database: EnumifiedDatabase {
+            url: Referenced::Reference("db_url".to_string()),
+            pool_size: Referenced::Value(2),
+        },
+    };
+
+    let mut base = Backend::Memory;
+    backend.apply_to_with(&mut base, &registry());
+    assert_eq!(
+        base,
+        Backend::Remote {
+            host: "example.com".to_string(),
+            database: Database {
+                url: "postgres://db".to_string(),
+                pool_size: 2,
+            },
+        }
+    );
+
+    EnumifiedBackend::Memory.apply_to_with(&mut base, &registry());
+    assert_eq!(base, Backend::Memory);
+}
@@ -0,0 +1,128 @@
+use enumify_struct::{enumify_struct, Applicable, ResolveToBase};
+
+#[derive(Debug, PartialEq, Clone)]
+enum Named<T> {
+    Value(T),
+    Reference(String),
+}
+
+impl<T: Clone + From<String>> ResolveToBase<T> for Named<T> {
+    fn resolve_to_base(&self) -> T {
+        match self {
+            Named::Value(v) => v.clone(),
+            Named::Reference(r) => T::from(format!("ref:{r}")),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+#[enumify_struct(Named)]
+struct Credentials {
+    user: String,
+    token: String,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[enumify_struct(Named)]
+enum Storage {
+    S3 {
+        bucket: String,
+        region: String,
+    },
+    Local {
+        path: String,
+    },
+    Remote(String, #[enumify_rename(EnumifiedCredentials)] Credentials),
+    Memory,
+    #[cfg(any())]
+    Disabled {
+        path: String,
+    },
+}
+
+fn s3() -> Storage {
+    Storage::S3 {
+        bucket: "bucket".into(),
+        region: "eu-west-1".into(),
+    }
+}
+
+#[test]
+fn test_same_variant_applies_field_wise() {
+    let layer = EnumifiedStorage::S3 {
+        bucket: Named::Value("other".into()),
+        region: Named::Reference("region".into()),
+    };
+
+    assert_eq!(
+        layer.build(s3()),
+        Storage::S3 {
+            bucket: "other".into(),
+            region: "ref:region".into(),
+        }
+    );
+}
+
+#[test]
+fn test_same_variant_applies_nested() {
+    let base = Storage::Remote(
+        "host".into(),
+        Credentials {
+            user: "user".into(),
+            token: "token".into(),
+        },
+    );
+    let layer = EnumifiedStorage::Remote(
+        Named::Value("other".into()),
+        EnumifiedCredentials {
+            user: Named::Value("admin".into()),
+            token: Named::Reference("secret".into()),
+        },
+    );
+
+    assert_eq!(
+        layer.build(base),
+        Storage::Remote(
+            "other".into(),
+            Credentials {
+                user: "admin".into(),
+                token: "ref:secret".into(),
+            }
+        )
+    );
+}
+
+#[test]
+fn test_different_variant_replaces_base() {
+    let layer = EnumifiedStorage::Local {
+        path: Named::Value("/tmp".into()),
+    };
+    assert_eq!(
+        layer.build(s3()),
+        Storage::Local {
+            path: "/tmp".into()
+        }
+    );
+
+    let layer = EnumifiedStorage::Remote(
+        Named::Value("host".into()),
+        EnumifiedCredentials {
+            user: Named::Value("admin".into()),
+            token: Named::Value("token".into()),
+        },
+    );
+    assert_eq!(
+        layer.build(s3()),
+        Storage::Remote(
+            "host".into(),
+            Credentials {
+                user: "admin".into(),
+                token: "token".into(),
+            }
+        )
+    );
+
+    let mut base = s3();
+    EnumifiedStorage::Memory.apply_to(&mut base);
+    assert_eq!(base, Storage::Memory);
+}
@@ -0,0 +1,55 @@
+use enumify_struct::{enumify_struct, Applicable, IntoBase};
+
+// Neither the payloads nor the enum implement `Clone`, so applying a layer
+// has to move the values out of it
+#[derive(Debug, PartialEq, Default)]
+struct NoClone(Vec<u8>);
+
+enum Owned<T> {
+    Value(T),
+}
+
+impl<T> IntoBase<T> for Owned<T> {
+    fn into_base(self) -> T {
+        match self {
+            Owned::Value(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Default)]
+#[enumify_struct(Owned, derive())]
+struct Inner {
+    payload: NoClone,
+}
+
+#[derive(Debug, PartialEq, Default)]
+#[enumify_struct(Owned, derive())]
+struct Outer {
+    payload: NoClone,
+    #[enumify_nested]
+    inner: Inner,
+    #[enumify_skip_wrap]
+    raw: NoClone,
+}
+
+#[test]
+fn test_values_are_moved_into_the_base() {
+    let layer = EnumifiedOuter {
+        payload: Owned::Value(NoClone(vec![1, 2])),
+        inner: EnumifiedInner {
+            payload: Owned::Value(NoClone(vec![3])),
+        },
+        raw: NoClone(vec![4]),
+    };
+    assert_eq!(
+        layer.build(Outer::default()),
+        Outer {
+            payload: NoClone(vec![1, 2]),
+            inner: Inner {
+                payload: NoClone(vec![3]),
+            },
+            raw: NoClone(vec![4]),
+        }
+    );
+}
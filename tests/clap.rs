@@ -0,0 +1,253 @@
+#![cfg(feature = "clap")]
+
+use clap::{Arg, ArgAction, Args, Command, FromArgMatches};
+use enumify_struct::{enumify_struct, Applicable, Layer};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Layer, derive(Debug, Default, PartialEq))]
+#[enumify_clap]
+struct Database {
+    /// Host of the database
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Layer, derive(Debug, Default, PartialEq))]
+#[enumify_clap]
+struct Config {
+    /// Level of the logs,
+    /// e.g. `debug`
+    log_level: String,
+    verbose: bool,
+    #[enumify_nested]
+    database: Database,
+    #[enumify_skip_wrap]
+    retries: u8,
+    #[enumify_skip_parse]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Layer, derive(Debug, Default, PartialEq))]
+#[enumify_clap]
+struct Service {
+    #[enumify_nested]
+    #[enumify_wrap]
+    replica: Database,
+    port: Layer<u16>,
+}
+
+fn dry_run(cmd: Command) -> Command {
+    cmd.arg(
+        Arg::new("dry-run")
+            .long("dry-run")
+            .action(ArgAction::SetTrue),
+    )
+}
+
+fn command() -> Command {
+    dry_run(EnumifiedConfig::augment_args(Command::new("app")))
+}
+
+fn update_command() -> Command {
+    dry_run(EnumifiedConfig::augment_args_for_update(Command::new(
+        "app",
+    )))
+}
+
+fn parse(args: &[&str]) -> EnumifiedConfig {
+    let matches = command()
+        .try_get_matches_from(
+            std::iter::once("app").chain(args.iter().copied()),
+        )
+        .unwrap();
+    EnumifiedConfig::from_arg_matches(&matches).unwrap()
+}
+
+#[test]
+fn test_flags_set_fields() {
+    let layer = parse(&[
+        "--log-level",
+        "debug",
+        "--database-port",
+        "6543",
+        "--retries",
+        "3",
+    ]);
+    assert_eq!(
+        layer,
+        EnumifiedConfig {
+            log_level: Layer::Set("debug".to_string()),
+            verbose: Layer::Unset,
+            database: EnumifiedDatabase {
+                host: Layer::Unset,
+                port: Layer::Set(6543),
+            },
+            retries: 3,
+            tags: Layer::Unset,
+        }
+    );
+
+    let base = Config {
+        log_level: "info".to_string(),
+        verbose: false,
+        database: Database {
+            host: "localhost".to_string(),
+            port: 5432,
+        },
+        retries: 5,
+        tags: vec!["primary".to_string()],
+    };
+    assert_eq!(
+        layer.build(base),
+        Config {
+            log_level: "debug".to_string(),
+            verbose: false,
+            database: Database {
+                host: "localhost".to_string(),
+                port: 6543,
+            },
+            retries: 3,
+            tags: vec!["primary".to_string()],
+        }
+    );
+}
+
+#[test]
+fn test_boolean_flags() {
+    let retries = ["--retries", "3"];
+    assert_eq!(
+        parse(&[&retries[..], &["--verbose"]].concat()).verbose,
+        Layer::Set(true)
+    );
+    assert_eq!(
+        parse(&[&retries[..], &["--verbose", "false"]].concat()).verbose,
+        Layer::Set(false)
+    );
+    assert_eq!(parse(&retries).verbose, Layer::Unset);
+}
+
+#[test]
+fn test_missing_required_flag_is_rejected() {
+    let error = command()
+        .try_get_matches_from(["app", "--verbose"])
+        .unwrap_err();
+    assert_eq!(
+        error.kind(),
+        clap::error::ErrorKind::MissingRequiredArgument
+    );
+
+    let matches = update_command()
+        .try_get_matches_from(["app", "--verbose"])
+        .unwrap();
+    let error = EnumifiedConfig::from_arg_matches(&matches).unwrap_err();
+    assert_eq!(
+        error.kind(),
+        clap::error::ErrorKind::MissingRequiredArgument
+    );
+}
+
+#[test]
+fn test_invalid_flag_is_rejected() {
+    let error = command()
+        .try_get_matches_from([
+            "app",
+            "--retries",
+            "3",
+            "--database-port",
+            "port",
+        ])
+        .unwrap_err();
+    assert_eq!(error.kind(), clap::error::ErrorKind::ValueValidation);
+}
+
+#[test]
+fn test_alongside_other_arguments() {
+    let matches = update_command()
+        .try_get_matches_from(["app", "--dry-run", "--database-host", "db"])
+        .unwrap();
+    assert!(matches.get_flag("dry-run"));
+
+    let mut layer = EnumifiedConfig {
+        log_level: Layer::Set("warn".to_string()),
+        retries: 5,
+        ..Default::default()
+    };
+    layer.update_from_arg_matches(&matches).unwrap();
+    assert_eq!(layer.database.host, Layer::Set("db".to_string()));
+    assert_eq!(layer.log_level, Layer::Set("warn".to_string()));
+    assert_eq!(layer.retries, 5);
+
+    let matches = update_command()
+        .try_get_matches_from(["app", "--retries", "3"])
+        .unwrap();
+    layer.update_from_arg_matches(&matches).unwrap();
+    assert_eq!(layer.retries, 3);
+}
+
+#[test]
+fn test_help_is_the_documentation() {
+    let help = command().render_help().to_string();
+    assert!(help.contains("--log-level <log-level>"));
+    assert!(help.contains("Level of the logs, e.g. `debug`"));
+    assert!(help.contains("--database-host <database-host>"));
+    assert!(help.contains("Host of the database"));
+    assert!(!help.contains("--tags"));
+}
+
+fn parse_service(args: &[&str]) -> Result<EnumifiedService, clap::Error> {
+    let matches = EnumifiedService::augment_args(Command::new("app"))
+        .try_get_matches_from(
+            std::iter::once("app").chain(args.iter().copied()),
+        )?;
+    EnumifiedService::from_arg_matches(&matches)
+}
+
+#[test]
+fn test_wrapped_nested_structure_is_set_by_its_flags() {
+    assert_eq!(
+        parse_service(&["--replica-host", "replica", "--port", "80"]).unwrap(),
+        EnumifiedService {
+            replica: Layer::Set(EnumifiedDatabase {
+                host: Layer::Set("replica".to_string()),
+                port: Layer::Unset,
+            }),
+            port: Layer::Set(80),
+        }
+    );
+    assert_eq!(
+        parse_service(&["--port", "80"]).unwrap().replica,
+        Layer::Unset
+    );
+
+    let matches =
+        EnumifiedService::augment_args_for_update(Command::new("app"))
+            .try_get_matches_from(["app", "--replica-port", "5433"])
+            .unwrap();
+    let mut layer = EnumifiedService {
+        replica: Layer::Set(EnumifiedDatabase {
+            host: Layer::Set("replica".to_string()),
+            port: Layer::Unset,
+        }),
+        port: Layer::Set(80),
+    };
+    layer.update_from_arg_matches(&matches).unwrap();
+    assert_eq!(
+        layer.replica,
+        Layer::Set(EnumifiedDatabase {
+            host: Layer::Set("replica".to_string()),
+            port: Layer::Set(5433),
+        })
+    );
+    assert_eq!(layer.port, Layer::Set(80));
+}
+
+#[test]
+fn test_target_enum_field_flag_is_required() {
+    let error = parse_service(&["--replica-port", "5433"]).unwrap_err();
+    assert_eq!(
+        error.kind(),
+        clap::error::ErrorKind::MissingRequiredArgument
+    );
+}
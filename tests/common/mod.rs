@@ -0,0 +1,15 @@
+use enumify_struct::IntoBase;
+
+/// The simplest target enum, always holding the value of the field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Plain<T> {
+    Value(T),
+}
+
+impl<T> IntoBase<T> for Plain<T> {
+    fn into_base(self) -> T {
+        match self {
+            Plain::Value(v) => v,
+        }
+    }
+}
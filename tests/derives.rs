@@ -0,0 +1,42 @@
+use enumify_struct::{enumify_struct, Applicable, IntoBase};
+
+/// Neither `PartialEq` nor `Debug`, which the generated structure derives by
+/// default.
+#[derive(Clone, Default)]
+enum Opaque<T> {
+    #[default]
+    Unset,
+    Value(T),
+}
+
+impl<T: Default> IntoBase<T> for Opaque<T> {
+    fn into_base(self) -> T {
+        match self {
+            Opaque::Unset => T::default(),
+            Opaque::Value(v) => v,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+#[enumify_struct(Opaque, remove_derive(PartialEq, Debug), add_derive(Default))]
+struct Config {
+    name: String,
+    port: u16,
+}
+
+#[test]
+fn test_configured_derives() {
+    let layer = EnumifiedConfig {
+        port: Opaque::Value(8080),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        layer.clone().build(Config::default()),
+        Config {
+            name: String::new(),
+            port: 8080,
+        }
+    );
+}
@@ -0,0 +1,68 @@
+use enumify_struct::{enumify_struct, Applicable, Enumify};
+
+use common::Plain;
+
+mod common;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Plain, LowerLayer)]
+struct Lower {
+    a: String,
+    b: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Plain)]
+struct Higher {
+    name: String,
+    #[enumify_nested]
+    lower: Lower,
+    #[enumify_nested]
+    #[enumify_wrap]
+    wrapped: Lower,
+}
+
+#[test]
+fn test_enumify_names_generated_struct() {
+    let lower: <Lower as Enumify>::Enumified = LowerLayer {
+        a: Plain::Value("a".to_string()),
+        b: Plain::Value(1),
+    };
+    assert_eq!(
+        lower.build(Lower::default()),
+        Lower {
+            a: "a".to_string(),
+            b: 1,
+        }
+    );
+}
+
+#[test]
+fn test_nested_fields_recurse() {
+    let higher = EnumifiedHigher {
+        name: Plain::Value("higher".to_string()),
+        lower: LowerLayer {
+            a: Plain::Value("a".to_string()),
+            b: Plain::Value(2),
+        },
+        wrapped: Plain::Value(LowerLayer {
+            a: Plain::Value("b".to_string()),
+            b: Plain::Value(3),
+        }),
+    };
+
+    assert_eq!(
+        higher.build(Higher::default()),
+        Higher {
+            name: "higher".to_string(),
+            lower: Lower {
+                a: "a".to_string(),
+                b: 2,
+            },
+            wrapped: Lower {
+                a: "b".to_string(),
+                b: 3,
+            },
+        }
+    );
+}
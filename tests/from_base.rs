@@ -0,0 +1,178 @@
+use enumify_struct::{enumify_struct, Applicable, FromBase, ResolveToBase};
+
+use common::Plain;
+
+mod common;
+
+impl<T> FromBase<T> for Plain<T> {
+    fn from_base(base: T) -> Self {
+        Plain::Value(base)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Plain)]
+struct Lower {
+    a: String,
+    b: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Plain)]
+struct Higher {
+    name: String,
+    #[enumify_nested]
+    lower: Lower,
+    #[enumify_nested]
+    #[enumify_wrap]
+    wrapped: Lower,
+    #[enumify_skip_wrap]
+    raw: Vec<u8>,
+    #[enumify_skip]
+    cache: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[enumify_struct(Plain)]
+enum Source {
+    File {
+        path: String,
+        #[enumify_skip]
+        handle: u32,
+    },
+    Inline(#[enumify_nested] Lower),
+    Stdin,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Plain)]
+struct Generic<T> {
+    value: T,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Plain)]
+struct Gated {
+    #[cfg(test)]
+    enabled: u32,
+    #[cfg(not(test))]
+    disabled: std::rc::Rc<u32>,
+    name: String,
+}
+
+/// Does not implement `FromBase`, so neither do the generated structures.
+#[derive(Debug, Clone, PartialEq)]
+enum Resolved<T> {
+    Value(T),
+}
+
+impl<T: Clone> ResolveToBase<T> for Resolved<T> {
+    fn resolve_to_base(&self) -> T {
+        match self {
+            Resolved::Value(v) => v.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Resolved)]
+struct ResolvedGated {
+    #[cfg(test)]
+    enabled: u8,
+    name: String,
+}
+
+fn lower_layer(a: &str, b: u32) -> EnumifiedLower {
+    EnumifiedLower {
+        a: Plain::Value(a.to_string()),
+        b: Plain::Value(b),
+    }
+}
+
+#[test]
+fn test_from_base_recurses_into_nested_fields() {
+    let higher = Higher {
+        name: "higher".to_string(),
+        lower: Lower {
+            a: "a".to_string(),
+            b: 1,
+        },
+        wrapped: Lower {
+            a: "b".to_string(),
+            b: 2,
+        },
+        raw: vec![3],
+        cache: 4,
+    };
+    let layer = EnumifiedHigher::from_base(higher.clone());
+    assert_eq!(
+        layer,
+        EnumifiedHigher {
+            name: Plain::Value("higher".to_string()),
+            lower: lower_layer("a", 1),
+            wrapped: Plain::Value(lower_layer("b", 2)),
+            raw: vec![3],
+        }
+    );
+
+    // Skipped fields are not part of the layer
+    assert_eq!(
+        layer.build(Higher::default()),
+        Higher { cache: 0, ..higher }
+    );
+}
+
+#[test]
+fn test_from_base_for_enums() {
+    let file = Source::File {
+        path: "config.toml".to_string(),
+        handle: 3,
+    };
+    assert_eq!(
+        EnumifiedSource::from(file),
+        EnumifiedSource::File {
+            path: Plain::Value("config.toml".to_string()),
+        }
+    );
+    assert_eq!(
+        EnumifiedSource::from(Source::Inline(Lower {
+            a: "a".to_string(),
+            b: 1,
+        })),
+        EnumifiedSource::Inline(lower_layer("a", 1))
+    );
+    assert_eq!(EnumifiedSource::from(Source::Stdin), EnumifiedSource::Stdin);
+}
+
+#[test]
+fn test_from_base_for_generic_structs() {
+    let layer: EnumifiedGeneric<u8> = Generic { value: 7 }.into();
+    assert_eq!(layer.value, Plain::Value(7));
+}
+
+#[test]
+fn test_from_base_with_cfg_gated_fields() {
+    let base = Gated {
+        enabled: 1,
+        name: "gated".to_string(),
+    };
+    assert_eq!(
+        EnumifiedGated::from(base.clone()),
+        EnumifiedGated {
+            enabled: Plain::Value(1),
+            name: Plain::Value("gated".to_string()),
+        }
+    );
+
+    let layer = EnumifiedResolvedGated {
+        enabled: Resolved::Value(2),
+        name: Resolved::Value("resolved".to_string()),
+    };
+    assert_eq!(
+        layer.build(ResolvedGated::default()),
+        ResolvedGated {
+            enabled: 2,
+            name: "resolved".to_string(),
+        }
+    );
+}
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use enumify_struct::{enumify_struct, Applicable, FromEnv, Layer};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Layer, derive(Debug, Default, PartialEq))]
+#[enumify_from_env]
+struct Database {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Layer, derive(Debug, Default, PartialEq))]
+#[enumify_from_env]
+struct Config {
+    log_level: String,
+    verbose: bool,
+    #[enumify_nested]
+    database: Database,
+    #[enumify_skip_wrap]
+    retries: u8,
+    #[enumify_skip_parse]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Layer, derive(Debug, Default, PartialEq))]
+#[enumify_from_env]
+struct Service {
+    #[enumify_nested]
+    #[enumify_wrap]
+    replica: Database,
+    port: Layer<u16>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Layer, derive(Debug, Default, PartialEq))]
+#[enumify_from_env]
+struct Limits<T> {
+    max: T,
+}
+
+fn vars(vars: &[(&str, &str)]) -> HashMap<String, String> {
+    vars.iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_variables_set_fields() {
+    let layer = EnumifiedConfig::from_env_vars(
+        "APP",
+        &vars(&[
+            ("APP_LOG_LEVEL", "debug"),
+            ("APP_DATABASE__PORT", "6543"),
+            ("APP_RETRIES", "3"),
+            ("OTHER_VERBOSE", "true"),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(
+        layer,
+        EnumifiedConfig {
+            log_level: Layer::Set("debug".to_string()),
+            verbose: Layer::Unset,
+            database: EnumifiedDatabase {
+                host: Layer::Unset,
+                port: Layer::Set(6543),
+            },
+            retries: 3,
+            tags: Layer::Unset,
+        }
+    );
+
+    let base = Config {
+        log_level: "info".to_string(),
+        verbose: true,
+        database: Database {
+            host: "localhost".to_string(),
+            port: 5432,
+        },
+        retries: 5,
+        tags: vec!["base".to_string()],
+    };
+    assert_eq!(
+        layer.build(base),
+        Config {
+            log_level: "debug".to_string(),
+            verbose: true,
+            database: Database {
+                host: "localhost".to_string(),
+                port: 6543,
+            },
+            retries: 3,
+            tags: vec!["base".to_string()],
+        }
+    );
+}
+
+#[test]
+fn test_missing_required_variable_is_reported() {
+    let error = EnumifiedConfig::from_env_vars(
+        "APP",
+        &vars(&[("APP_LOG_LEVEL", "debug")]),
+    )
+    .unwrap_err();
+    assert_eq!(error.path(), "APP_RETRIES");
+    assert_eq!(
+        error.to_string(),
+        "failed to resolve `APP_RETRIES`: the variable is not set"
+    );
+}
+
+#[test]
+fn test_empty_prefix() {
+    let layer = EnumifiedDatabase::from_env_vars(
+        "",
+        &vars(&[("HOST", "db"), ("_PORT", "1")]),
+    )
+    .unwrap();
+    assert_eq!(layer.host, Layer::Set("db".to_string()));
+    assert_eq!(layer.port, Layer::Unset);
+}
+
+#[test]
+fn test_invalid_variable_is_reported() {
+    let error = EnumifiedConfig::from_env_vars(
+        "APP",
+        &vars(&[("APP_DATABASE__PORT", "port")]),
+    )
+    .unwrap_err();
+    assert_eq!(error.path(), "APP_DATABASE__PORT");
+    assert_eq!(
+        error.to_string(),
+        "failed to resolve `APP_DATABASE__PORT`: invalid digit found in string"
+    );
+}
+
+#[test]
+fn test_generic_fields() {
+    let layer = EnumifiedLimits::<u32>::from_env_vars(
+        "APP",
+        &vars(&[("APP_MAX", "8")]),
+    )
+    .unwrap();
+    assert_eq!(layer.max, Layer::Set(8));
+}
+
+#[test]
+fn test_process_environment() {
+    std::env::set_var("ENUMIFY_FROM_ENV_TEST_HOST", "db");
+    let layer = EnumifiedDatabase::from_env("ENUMIFY_FROM_ENV_TEST").unwrap();
+    assert_eq!(layer.host, Layer::Set("db".to_string()));
+    assert_eq!(layer.port, Layer::Unset);
+}
+
+#[test]
+fn test_wrapped_nested_structure_is_set_by_its_variables() {
+    let layer = EnumifiedService::from_env_vars(
+        "APP",
+        &vars(&[("APP_REPLICA__HOST", "replica"), ("APP_PORT", "80")]),
+    )
+    .unwrap();
+    assert_eq!(
+        layer,
+        EnumifiedService {
+            replica: Layer::Set(EnumifiedDatabase {
+                host: Layer::Set("replica".to_string()),
+                port: Layer::Unset,
+            }),
+            port: Layer::Set(80),
+        }
+    );
+
+    let base = Service {
+        replica: Database {
+            host: "localhost".to_string(),
+            port: 5433,
+        },
+        port: Layer::Unset,
+    };
+    assert_eq!(
+        layer.build(base),
+        Service {
+            replica: Database {
+                host: "replica".to_string(),
+                port: 5433,
+            },
+            port: Layer::Set(80),
+        }
+    );
+
+    let layer =
+        EnumifiedService::from_env_vars("APP", &vars(&[("APP_PORT", "80")]))
+            .unwrap();
+    assert_eq!(layer.replica, Layer::Unset);
+}
+
+#[test]
+fn test_target_enum_field_variable_is_required() {
+    let error = EnumifiedService::from_env_vars(
+        "APP",
+        &vars(&[("APP_REPLICA__PORT", "5433")]),
+    )
+    .unwrap_err();
+    assert_eq!(error.path(), "APP_PORT");
+}
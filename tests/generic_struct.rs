@@ -0,0 +1,236 @@
+use std::{borrow::Cow, fmt::Debug};
+
+use enumify_struct::{
+    enumify_struct, Applicable, ResolveToBase, TryApplicable,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Defaulted<T> {
+    Value(T),
+    Default,
+}
+
+impl<T: Clone + Default> ResolveToBase<T> for Defaulted<T> {
+    fn resolve_to_base(&self) -> T {
+        match self {
+            Defaulted::Value(v) => v.clone(),
+            Defaulted::Default => T::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Defaulted)]
+struct Pair<T, U = String>
+where
+    T: Copy + Default,
+    U: Clone + Default,
+{
+    first: T,
+    second: U,
+    count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[enumify_struct(Defaulted)]
+struct Buffer<const N: usize>
+where
+    [u8; N]: Default,
+{
+    data: [u8; N],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[enumify_struct(Defaulted)]
+struct Named<'a> {
+    name: Cow<'a, str>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Defaulted)]
+struct Outer<T: Debug>
+where
+    T: Copy + Default,
+{
+    #[enumify_nested]
+    pair: Pair<T>,
+    label: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[enumify_struct(Defaulted)]
+enum Either<L: Clone + Default, R: Copy + Default> {
+    Left(L),
+    Right {
+        value: R,
+        #[enumify_nested]
+        pair: Pair<u8, R>,
+    },
+}
+
+#[test]
+fn test_where_clause_and_defaulted_param() {
+    let layer: EnumifiedPair<u8> = EnumifiedPair {
+        first: Defaulted::Value(1),
+        second: Defaulted::Value("second".to_string()),
+        count: Defaulted::Default,
+    };
+
+    let base = layer.try_build(Pair::default()).unwrap();
+    assert_eq!(
+        base,
+        Pair {
+            first: 1,
+            second: "second".to_string(),
+            count: 0,
+        }
+    );
+}
+
+#[test]
+fn test_const_generic() {
+    let layer = EnumifiedBuffer {
+        data: Defaulted::Value([1, 2, 3]),
+    };
+    assert_eq!(
+        layer.build(Buffer { data: [0; 3] }),
+        Buffer { data: [1, 2, 3] }
+    );
+}
+
+#[test]
+fn test_lifetime() {
+    let name = "borrowed".to_string();
+    let layer = EnumifiedNamed {
+        name: Defaulted::Value(Cow::Borrowed(name.as_str())),
+    };
+    assert_eq!(
+        layer.build(Named {
+            name: Cow::Owned("owned".to_string())
+        }),
+        Named {
+            name: Cow::Borrowed("borrowed")
+        }
+    );
+}
+
+#[test]
+fn test_nested_generic() {
+    let layer = EnumifiedOuter::<i64> {
+        pair: EnumifiedPair {
+            first: Defaulted::Value(-1),
+            second: Defaulted::Value("nested".to_string()),
+            count: Defaulted::Value(2),
+        },
+        label: Defaulted::Default,
+    };
+    assert_eq!(
+        layer.build(Outer::default()),
+        Outer {
+            pair: Pair {
+                first: -1,
+                second: "nested".to_string(),
+                count: 2,
+            },
+            label: String::new(),
+        }
+    );
+}
+
+#[test]
+fn test_generic_enum() {
+    let layer = EnumifiedEither::<String, i32>::Right {
+        value: Defaulted::Value(3),
+        pair: EnumifiedPair {
+            first: Defaulted::Value(4),
+            second: Defaulted::Default,
+            count: Defaulted::Value(5),
+        },
+    };
+    assert_eq!(
+        layer.build(Either::Left("left".to_string())),
+        Either::Right {
+            value: 3,
+            pair: Pair {
+                first: 4,
+                second: 0,
+                count: 5,
+            },
+        }
+    );
+
+    let layer = EnumifiedEither::<String, i32>::Left(Defaulted::Default);
+    assert_eq!(
+        layer.build(Either::Left("left".to_string())),
+        Either::Left(String::new())
+    );
+}
+
+mod crate_layer {
+    use enumify_struct::{enumify_struct, Applicable, Layer, TryApplicable};
+
+    #[derive(Debug, Clone, PartialEq)]
+    #[enumify_struct(Layer)]
+    struct Gen<T> {
+        x: T,
+        y: u8,
+    }
+
+    #[test]
+    fn test_unbounded_parameter() {
+        let layer = EnumifiedGen {
+            x: Layer::Set(vec![1]),
+            y: Layer::Unset,
+        };
+        let mut base = Gen { x: vec![], y: 2 };
+        layer.clone().apply_to(&mut base);
+        assert_eq!(base, Gen { x: vec![1], y: 2 });
+
+        let base = layer.try_build(Gen { x: vec![], y: 3 }).unwrap();
+        assert_eq!(base, Gen { x: vec![1], y: 3 });
+    }
+}
+
+mod into_base {
+    use enumify_struct::{enumify_struct, Applicable, IntoBase};
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Owned<T> {
+        Value(T),
+        Boxed(Box<T>),
+    }
+
+    impl<T> IntoBase<T> for Owned<T> {
+        fn into_base(self) -> T {
+            match self {
+                Owned::Value(v) => v,
+                Owned::Boxed(b) => *b,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    #[enumify_struct(Owned)]
+    struct Gen<T> {
+        x: T,
+        y: String,
+    }
+
+    #[test]
+    fn test_into_base() {
+        let layer = EnumifiedGen {
+            x: Owned::Boxed(Box::new(1)),
+            y: Owned::Value("y".to_string()),
+        };
+        assert_eq!(
+            layer.build(Gen {
+                x: 0,
+                y: String::new()
+            }),
+            Gen {
+                x: 1,
+                y: "y".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use enumify_struct::{
+    enumify_struct, Applicable, Layer, Mergeable, TryApplicable,
+};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Layer, derive(Debug, Default, PartialEq))]
+struct Database {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Layer, derive(Debug, Default, PartialEq))]
+struct Config {
+    name: String,
+    verbose: bool,
+    #[enumify_nested]
+    database: Database,
+    #[enumify_nested]
+    #[enumify_wrap]
+    replica: Database,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[enumify_struct(Layer, derive(Debug, PartialEq))]
+enum Output {
+    File { path: String, append: bool },
+    Stdout,
+}
+
+fn base() -> Config {
+    Config {
+        name: "base".to_string(),
+        verbose: true,
+        database: Database {
+            host: "localhost".to_string(),
+            port: 5432,
+        },
+        replica: Database {
+            host: "replica".to_string(),
+            port: 5433,
+        },
+    }
+}
+
+#[test]
+fn test_unset_fields_are_left_untouched() {
+    let layer = EnumifiedConfig {
+        name: Layer::Set("layer".to_string()),
+        database: EnumifiedDatabase {
+            port: Layer::Set(6543),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    assert_eq!(
+        layer.build(base()),
+        Config {
+            name: "layer".to_string(),
+            database: Database {
+                host: "localhost".to_string(),
+                port: 6543,
+            },
+            ..base()
+        }
+    );
+    assert_eq!(
+        EnumifiedConfig::default().try_build(base()).unwrap(),
+        base()
+    );
+}
+
+#[test]
+fn test_set_nested_layers_are_applied() {
+    let layer = EnumifiedConfig {
+        replica: Layer::Set(EnumifiedDatabase {
+            host: Layer::Set("other".to_string()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    assert_eq!(layer.build(base()).replica.host, "other");
+}
+
+#[test]
+fn test_merged_layers_are_applied_once() {
+    let file = EnumifiedConfig {
+        name: Layer::Set("file".to_string()),
+        verbose: Layer::Set(false),
+        ..Default::default()
+    };
+    let cli = EnumifiedConfig {
+        name: Layer::Set("cli".to_string()),
+        ..Default::default()
+    };
+    let config = file.merge(cli).build(base());
+    assert_eq!(config.name, "cli");
+    assert!(!config.verbose);
+}
+
+#[test]
+fn test_unset_fields_of_a_replacing_variant_default() {
+    let mut output = Output::Stdout;
+    EnumifiedOutput::File {
+        path: Layer::Set("out.log".to_string()),
+        append: Layer::Unset,
+    }
+    .apply_to(&mut output);
+    assert_eq!(
+        output,
+        Output::File {
+            path: "out.log".to_string(),
+            append: false,
+        }
+    );
+
+    EnumifiedOutput::File {
+        path: Layer::Unset,
+        append: Layer::Set(true),
+    }
+    .apply_to(&mut output);
+    assert_eq!(
+        output,
+        Output::File {
+            path: "out.log".to_string(),
+            append: true,
+        }
+    );
+}
@@ -0,0 +1,104 @@
+use enumify_struct::{enumify_struct, Layer, LayerStack};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Layer, derive(Debug, Default, PartialEq))]
+struct Database {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Layer, derive(Debug, Default, PartialEq))]
+struct Config {
+    name: String,
+    verbose: bool,
+    #[enumify_nested]
+    database: Database,
+}
+
+// A layer of another type, e.g. holding defaults computed at runtime
+struct Verbose;
+
+impl enumify_struct::Applicable for Verbose {
+    type Base = Config;
+
+    fn apply_to(self, base: &mut Config) {
+        base.verbose = true;
+    }
+}
+
+fn file() -> EnumifiedConfig {
+    EnumifiedConfig {
+        name: Layer::Set("file".to_string()),
+        database: EnumifiedDatabase {
+            host: Layer::Set("db".to_string()),
+            port: Layer::Set(5432),
+        },
+        ..Default::default()
+    }
+}
+
+fn env() -> EnumifiedConfig {
+    EnumifiedConfig {
+        name: Layer::Set("env".to_string()),
+        database: EnumifiedDatabase {
+            port: Layer::Set(6543),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_layers_are_applied_by_priority() {
+    let mut stack = LayerStack::new();
+    stack
+        .push("env", 20, env())
+        .push("file", 10, file())
+        .push("verbose", 0, Verbose);
+    assert_eq!(
+        stack.names().collect::<Vec<_>>(),
+        ["verbose", "file", "env"]
+    );
+    assert_eq!(
+        stack.build(),
+        Config {
+            name: "env".to_string(),
+            verbose: true,
+            database: Database {
+                host: "db".to_string(),
+                port: 6543,
+            },
+        }
+    );
+}
+
+#[test]
+fn test_equal_priorities_keep_insertion_order() {
+    let stack = LayerStack::new().with_layer("env", 10, env()).with_layer(
+        "file",
+        10,
+        file(),
+    );
+    assert_eq!(stack.names().collect::<Vec<_>>(), ["env", "file"]);
+    assert_eq!(stack.build().name, "file");
+}
+
+#[test]
+fn test_build_from_base() {
+    let base = Config {
+        name: "base".to_string(),
+        ..Default::default()
+    };
+    let stack = LayerStack::new().with_layer("verbose", 0, Verbose);
+    assert_eq!(stack.len(), 1);
+    assert_eq!(
+        stack.build_from(base),
+        Config {
+            name: "base".to_string(),
+            verbose: true,
+            ..Default::default()
+        }
+    );
+    assert!(LayerStack::<Config>::default().is_empty());
+}
@@ -0,0 +1,170 @@
+use enumify_struct::{enumify_struct, Applicable, Layer, Mergeable};
+
+use common::Plain;
+
+mod common;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Layer)]
+struct Lower {
+    a: String,
+    b: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Layer)]
+struct Higher {
+    name: String,
+    #[enumify_nested]
+    lower: Lower,
+    #[enumify_skip_wrap]
+    raw: Vec<u8>,
+    #[enumify_skip]
+    cache: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[enumify_struct(Layer)]
+enum Source {
+    File {
+        path: String,
+        #[enumify_skip]
+        handle: u32,
+    },
+    Inline(u32, #[enumify_skip] u8, String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Layer)]
+struct Gated {
+    #[cfg(test)]
+    enabled: u32,
+    #[cfg(not(test))]
+    disabled: std::rc::Rc<u32>,
+    name: String,
+}
+
+// `Plain` does not implement `Mergeable`, so neither does the generated
+// structure
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Plain)]
+struct PlainGated {
+    #[cfg(test)]
+    enabled: u8,
+    name: String,
+}
+
+fn higher_layer(
+    name: Layer<String>,
+    a: Layer<String>,
+    b: Layer<u32>,
+) -> EnumifiedHigher {
+    EnumifiedHigher {
+        name,
+        lower: EnumifiedLower { a, b },
+        raw: vec![],
+    }
+}
+
+#[test]
+fn test_merge_recurses_into_nested_layers() {
+    let file = higher_layer(
+        Layer::Set("file".to_string()),
+        Layer::Set("a".to_string()),
+        Layer::Unset,
+    );
+    let env = higher_layer(Layer::Unset, Layer::Unset, Layer::Set(1));
+    let cli = EnumifiedHigher {
+        raw: vec![2],
+        ..higher_layer(
+            Layer::Set("cli".to_string()),
+            Layer::Unset,
+            Layer::Unset,
+        )
+    };
+
+    let merged = [env, cli].into_iter().fold(file, Mergeable::merge);
+    assert_eq!(
+        merged,
+        EnumifiedHigher {
+            raw: vec![2],
+            ..higher_layer(
+                Layer::Set("cli".to_string()),
+                Layer::Set("a".to_string()),
+                Layer::Set(1),
+            )
+        }
+    );
+    assert_eq!(
+        merged.build(Higher {
+            cache: 3,
+            ..Default::default()
+        }),
+        Higher {
+            name: "cli".to_string(),
+            lower: Lower {
+                a: "a".to_string(),
+                b: 1,
+            },
+            raw: vec![2],
+            cache: 3,
+        }
+    );
+}
+
+#[test]
+fn test_merge_enums() {
+    let file = EnumifiedSource::File {
+        path: Layer::Set("config.toml".to_string()),
+    };
+    let inline = EnumifiedSource::Inline(Layer::Set(1), Layer::Unset);
+
+    // Layers holding different variants are not merged field-wise
+    assert_eq!(file.clone().merge(inline.clone()), inline);
+    assert_eq!(
+        inline.merge(EnumifiedSource::Inline(
+            Layer::Unset,
+            Layer::Set("inline".to_string())
+        )),
+        EnumifiedSource::Inline(
+            Layer::Set(1),
+            Layer::Set("inline".to_string())
+        )
+    );
+    assert_eq!(
+        file.clone()
+            .merge(EnumifiedSource::File { path: Layer::Unset }),
+        file
+    );
+}
+
+#[test]
+fn test_merge_with_cfg_gated_fields() {
+    let merged = EnumifiedGated {
+        enabled: Layer::Set(1),
+        name: Layer::Set("file".to_string()),
+    }
+    .merge(EnumifiedGated {
+        enabled: Layer::Set(2),
+        name: Layer::Unset,
+    });
+    assert_eq!(
+        merged,
+        EnumifiedGated {
+            enabled: Layer::Set(2),
+            name: Layer::Set("file".to_string()),
+        }
+    );
+
+    let layer = EnumifiedPlainGated {
+        enabled: Plain::Value(3),
+        name: Plain::Value("plain".to_string()),
+    };
+    assert_eq!(
+        layer.build(PlainGated::default()),
+        PlainGated {
+            enabled: 3,
+            name: "plain".to_string(),
+        }
+    );
+}
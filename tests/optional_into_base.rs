@@ -0,0 +1,101 @@
+use enumify_struct::{
+    enumify_struct, Applicable, OptionalIntoBase, TryApplicable,
+};
+
+/// Where a setting comes from, `Inherited` ones keeping the current value.
+#[derive(Debug, Clone, PartialEq)]
+enum Source<T> {
+    Inherited,
+    File(T),
+    Env(T),
+}
+
+impl<T> OptionalIntoBase<T> for Source<T> {
+    fn into_optional_base(self) -> Option<T> {
+        match self {
+            Source::Inherited => None,
+            Source::File(v) | Source::Env(v) => Some(v),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Source)]
+struct Logging {
+    level: String,
+    color: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Source)]
+struct Config {
+    name: String,
+    #[enumify_nested]
+    #[enumify_wrap]
+    logging: Logging,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[enumify_struct(Source)]
+enum Mode {
+    Server { port: u16, workers: usize },
+    Client,
+}
+
+fn base() -> Config {
+    Config {
+        name: "base".to_string(),
+        logging: Logging {
+            level: "info".to_string(),
+            color: true,
+        },
+    }
+}
+
+#[test]
+fn test_inherited_fields_are_left_untouched() {
+    let layer = EnumifiedConfig {
+        name: Source::Inherited,
+        logging: Source::Env(EnumifiedLogging {
+            level: Source::File("debug".to_string()),
+            color: Source::Inherited,
+        }),
+    };
+    assert_eq!(
+        layer.clone().build(base()),
+        Config {
+            logging: Logging {
+                level: "debug".to_string(),
+                color: true,
+            },
+            ..base()
+        }
+    );
+    assert_eq!(
+        layer.clone().try_build(base()).unwrap(),
+        layer.build(base())
+    );
+
+    let inherited = EnumifiedConfig {
+        name: Source::Inherited,
+        logging: Source::Inherited,
+    };
+    assert_eq!(inherited.build(base()), base());
+}
+
+#[test]
+fn test_inherited_fields_of_a_replacing_variant_default() {
+    let mut mode = Mode::Client;
+    EnumifiedMode::Server {
+        port: Source::Env(8080),
+        workers: Source::Inherited,
+    }
+    .apply_to(&mut mode);
+    assert_eq!(
+        mode,
+        Mode::Server {
+            port: 8080,
+            workers: 0,
+        }
+    );
+}
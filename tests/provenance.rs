@@ -0,0 +1,180 @@
+use enumify_struct::{enumify_struct, Applicable, Layer, LayerStack};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Layer, derive(Debug, Default, PartialEq))]
+struct Database {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Layer, derive(Debug, PartialEq))]
+enum Output {
+    File {
+        path: String,
+        append: bool,
+    },
+    #[default]
+    Stdout,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Layer, derive(Debug, Default, PartialEq))]
+struct Config {
+    name: String,
+    verbose: bool,
+    #[enumify_nested]
+    database: Database,
+    #[enumify_nested]
+    #[enumify_wrap]
+    replica: Database,
+    #[enumify_nested]
+    #[enumify_wrap]
+    output: Output,
+}
+
+// Layers implementing `Applicable` by hand record nothing
+struct Verbose;
+
+impl Applicable for Verbose {
+    type Base = Config;
+
+    fn apply_to(self, base: &mut Config) {
+        base.verbose = true;
+    }
+}
+
+#[test]
+fn test_last_layer_setting_a_field_is_recorded() {
+    let file = EnumifiedConfig {
+        name: Layer::Set("file".to_string()),
+        database: EnumifiedDatabase {
+            host: Layer::Set("db".to_string()),
+            port: Layer::Set(5432),
+        },
+        replica: Layer::Set(EnumifiedDatabase {
+            host: Layer::Set("replica".to_string()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let env = EnumifiedConfig {
+        database: EnumifiedDatabase {
+            port: Layer::Set(6543),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let cli = EnumifiedConfig {
+        verbose: Layer::Set(false),
+        output: Layer::Set(EnumifiedOutput::File {
+            path: Layer::Set("out.log".to_string()),
+            append: Layer::Unset,
+        }),
+        ..Default::default()
+    };
+
+    let (config, provenance) = LayerStack::new()
+        .with_layer("cli", 30, cli)
+        .with_layer("env", 20, env)
+        .with_layer("file", 10, file)
+        .with_layer("verbose", 0, Verbose)
+        .build_with_provenance();
+    assert!(!config.verbose);
+    assert_eq!(config.database.port, 6543);
+    assert_eq!(
+        provenance.iter().collect::<Vec<_>>(),
+        [
+            ("database.host", "file"),
+            ("database.port", "env"),
+            ("name", "file"),
+            // Replacing the variant of an enum sets it along with all of
+            // its fields
+            ("output", "cli"),
+            ("output.append", "cli"),
+            ("output.path", "cli"),
+            ("replica.host", "file"),
+            ("verbose", "cli"),
+        ]
+    );
+    assert_eq!(provenance.get("database.port"), Some("env"));
+    assert_eq!(provenance.get("replica.port"), None);
+}
+
+#[test]
+fn test_replaced_variant_forgets_its_fields() {
+    let file = || EnumifiedConfig {
+        output: Layer::Set(EnumifiedOutput::File {
+            path: Layer::Set("out.log".to_string()),
+            append: Layer::Set(true),
+        }),
+        ..Default::default()
+    };
+    let env = EnumifiedConfig {
+        output: Layer::Set(EnumifiedOutput::File {
+            path: Layer::Set("env.log".to_string()),
+            append: Layer::Unset,
+        }),
+        ..Default::default()
+    };
+    let cli = EnumifiedConfig {
+        output: Layer::Set(EnumifiedOutput::Stdout),
+        ..Default::default()
+    };
+
+    // Setting the fields of the same variant leaves the others as is
+    let (config, provenance) = LayerStack::new()
+        .with_layer("env", 20, env)
+        .with_layer("file", 10, file())
+        .build_with_provenance();
+    assert_eq!(
+        config.output,
+        Output::File {
+            path: "env.log".to_string(),
+            append: true,
+        }
+    );
+    assert_eq!(
+        provenance.iter().collect::<Vec<_>>(),
+        [
+            ("output", "file"),
+            ("output.append", "file"),
+            ("output.path", "env"),
+        ]
+    );
+
+    let (config, provenance) = LayerStack::new()
+        .with_layer("cli", 30, cli)
+        .with_layer("file", 10, file())
+        .build_with_provenance();
+    assert_eq!(config.output, Output::Stdout);
+    assert_eq!(provenance.iter().collect::<Vec<_>>(), [("output", "cli")]);
+}
+
+#[test]
+fn test_apply_to_tracked_records_paths() {
+    let layer = EnumifiedDatabase {
+        host: Layer::Set("db".to_string()),
+        port: Layer::Unset,
+    };
+    let mut paths = Vec::new();
+    let mut base = Database::default();
+    layer.apply_to_tracked(&mut base, "primary", &mut |path| {
+        paths.push(path.to_owned())
+    });
+    assert_eq!(paths, ["primary.host"]);
+    assert_eq!(base.host, "db");
+}
+
+#[test]
+fn test_empty_stack_has_no_provenance() {
+    let base = Config {
+        name: "base".to_string(),
+        ..Default::default()
+    };
+    let (config, provenance) =
+        LayerStack::new().build_from_with_provenance(base.clone());
+    assert_eq!(config, base);
+    assert!(provenance.is_empty());
+    assert_eq!(provenance.len(), 0);
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use enumify_struct::{
+    enumify_struct, ApplicableWith, Mergeable, Ref, ReferenceError,
+    TryApplicableWith,
+};
+
+type Registry = HashMap<String, String>;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Ref)]
+#[enumify_context(Registry)]
+struct Database {
+    url: String,
+    pool_size: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Ref)]
+#[enumify_context(Registry)]
+struct Config {
+    name: String,
+    #[enumify_nested]
+    database: Database,
+}
+
+fn registry() -> Registry {
+    HashMap::from([
+        ("db_url".to_string(), "postgres://db".to_string()),
+        ("pool".to_string(), "16".to_string()),
+    ])
+}
+
+fn config(pool_size: Ref<u32>) -> EnumifiedConfig {
+    EnumifiedConfig {
+        name: Ref::Value("app".to_string()),
+        database: EnumifiedDatabase {
+            url: Ref::Reference("db_url".to_string()),
+            pool_size,
+        },
+    }
+}
+
+#[test]
+fn test_references_are_looked_up_in_the_registry() {
+    let expected = Config {
+        name: "app".to_string(),
+        database: Database {
+            url: "postgres://db".to_string(),
+            pool_size: 16,
+        },
+    };
+    let pool_size = || Ref::Reference("pool".to_string());
+    assert_eq!(
+        config(pool_size()).build_with(Config::default(), &registry()),
+        expected
+    );
+    assert_eq!(
+        config(pool_size())
+            .try_build_with(Config::default(), &registry())
+            .unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_unknown_references_are_reported() {
+    let err = config(Ref::Reference("missing".to_string()))
+        .try_build_with(Config::default(), &registry())
+        .unwrap_err();
+    assert_eq!(err.path(), "database.pool_size");
+    assert!(matches!(
+        err.into_source().downcast_ref::<ReferenceError>(),
+        Some(ReferenceError::Unknown(name)) if name == "missing"
+    ));
+}
+
+#[test]
+fn test_invalid_references_are_reported() {
+    let err = config(Ref::Reference("db_url".to_string()))
+        .try_build_with(Config::default(), &registry())
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to resolve `database.pool_size`: invalid value for reference \
+         `db_url`: invalid digit found in string"
+    );
+}
+
+#[test]
+#[should_panic(expected = "unknown reference `missing`")]
+fn test_apply_to_with_panics_on_unknown_references() {
+    config(Ref::Reference("missing".to_string()))
+        .build_with(Config::default(), &registry());
+}
+
+#[test]
+fn test_merged_layers_are_resolved_once() {
+    let file = config(Ref::Value(4));
+    let env = config(Ref::Reference("pool".to_string()));
+    assert_eq!(
+        file.clone()
+            .merge(env)
+            .try_build_with(Config::default(), &registry())
+            .unwrap()
+            .database
+            .pool_size,
+        16
+    );
+
+    let cli = config(Ref::Reference("missing".to_string()));
+    let err = file
+        .merge(cli)
+        .try_build_with(Config::default(), &registry())
+        .unwrap_err();
+    assert_eq!(err.path(), "database.pool_size");
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Ref)]
+#[enumify_context(HashMap<String, Ref<u32>>)]
+struct Limits {
+    max_connections: u32,
+    max_requests: u32,
+}
+
+fn aliases(entries: &[(&str, Ref<u32>)]) -> HashMap<String, Ref<u32>> {
+    entries
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.clone()))
+        .collect()
+}
+
+#[test]
+fn test_reference_chains_are_followed() {
+    let registry = aliases(&[
+        ("default", Ref::Value(64)),
+        ("connections", Ref::Reference("default".to_string())),
+    ]);
+    let limits = EnumifiedLimits {
+        max_connections: Ref::Reference("connections".to_string()),
+        max_requests: Ref::Value(128),
+    };
+    assert_eq!(
+        limits.try_build_with(Limits::default(), &registry).unwrap(),
+        Limits {
+            max_connections: 64,
+            max_requests: 128,
+        }
+    );
+}
+
+#[test]
+fn test_reference_cycles_are_reported() {
+    let registry = aliases(&[
+        ("a", Ref::Reference("b".to_string())),
+        ("b", Ref::Reference("c".to_string())),
+        ("c", Ref::Reference("b".to_string())),
+    ]);
+    let limits = EnumifiedLimits {
+        max_connections: Ref::Value(1),
+        max_requests: Ref::Reference("a".to_string()),
+    };
+    let err = limits
+        .try_build_with(Limits::default(), &registry)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to resolve `max_requests`: reference cycle: `a` -> `b` -> `c` \
+         -> `b`"
+    );
+    assert!(matches!(
+        err.into_source().downcast_ref::<ReferenceError>(),
+        Some(ReferenceError::Cycle(chain)) if chain == &["a", "b", "c", "b"]
+    ));
+}
@@ -0,0 +1,142 @@
+#![cfg(feature = "serde")]
+
+use std::collections::HashMap;
+
+use enumify_struct::{
+    enumify_struct, Applicable, Layer, Ref, TryResolveWithContext,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[enumify_struct(Layer, add_derive(Serialize, Deserialize))]
+struct Database {
+    #[serde(rename = "host_name")]
+    host: String,
+    #[serde(default = "default_port")]
+    port: u16,
+}
+
+fn default_port() -> u16 {
+    5432
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[enumify_struct(Layer, add_derive(Deserialize))]
+#[serde(rename_all = "kebab-case")]
+struct Config {
+    app_name: String,
+    #[enumify_nested]
+    database: Database,
+}
+
+// Placed before the derive so that the macro sees it
+#[enumify_struct(Layer)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+struct Secrets {
+    password: String,
+}
+
+// The generated structure does not derive the `serde` traits, so it does not
+// get the `serde` attributes either
+#[enumify_struct(Layer, remove_derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Plain {
+    #[serde(rename = "user")]
+    user_name: String,
+}
+
+#[test]
+fn test_missing_fields_are_unset() {
+    let layer: EnumifiedConfig =
+        serde_json::from_str(r#"{ "database": { "host_name": "db" } }"#)
+            .unwrap();
+    assert_eq!(layer.app_name, Layer::Unset);
+    assert_eq!(layer.database.host, Layer::Set("db".to_string()));
+    assert_eq!(layer.database.port, Layer::Unset);
+
+    let base = Config {
+        app_name: "app".to_string(),
+        database: Database {
+            host: "localhost".to_string(),
+            port: 6543,
+        },
+    };
+    assert_eq!(
+        layer.build(base),
+        Config {
+            app_name: "app".to_string(),
+            database: Database {
+                host: "db".to_string(),
+                port: 6543,
+            },
+        }
+    );
+}
+
+#[test]
+fn test_renames_are_forwarded() {
+    let layer: EnumifiedConfig = serde_json::from_str(
+        r#"{ "app-name": "app", "database": { "host_name": "db", "port": 1 } }"#,
+    )
+    .unwrap();
+    assert_eq!(layer.app_name, Layer::Set("app".to_string()));
+    assert_eq!(layer.database.port, Layer::Set(1));
+
+    let database = EnumifiedDatabase {
+        host: Layer::Set("db".to_string()),
+        port: Layer::Unset,
+    };
+    assert_eq!(
+        serde_json::to_string(&database).unwrap(),
+        r#"{"host_name":"db","port":null}"#
+    );
+    let round_trip: EnumifiedDatabase =
+        serde_json::from_str(&serde_json::to_string(&database).unwrap())
+            .unwrap();
+    assert_eq!(round_trip, database);
+}
+
+#[test]
+fn test_option_payloads_are_not_supported() {
+    let layer: Layer<Option<u16>> = Layer::Set(None);
+    let json = serde_json::to_string(&layer).unwrap();
+    assert_eq!(json, "null");
+    let round_trip: Layer<Option<u16>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(round_trip, Layer::Unset);
+}
+
+#[test]
+fn test_derive_before_the_macro() {
+    let layer: EnumifiedSecrets =
+        serde_json::from_str(r#"{ "password": "hunter2" }"#).unwrap();
+    assert_eq!(layer.password, Layer::Set("hunter2".to_string()));
+    let layer: EnumifiedSecrets = serde_json::from_str("{}").unwrap();
+    assert_eq!(layer, EnumifiedSecrets::default());
+}
+
+#[test]
+fn test_serde_attributes_need_serde_derives() {
+    let layer = EnumifiedPlain {
+        user_name: Layer::Set("admin".to_string()),
+    };
+    let plain = layer.build(Plain::default());
+    assert_eq!(
+        serde_json::to_string(&plain).unwrap(),
+        r#"{"user":"admin"}"#
+    );
+}
+
+#[test]
+fn test_references_are_deserialized() {
+    let reference: Ref<String> =
+        serde_json::from_str(r#"{ "reference": "db_password" }"#).unwrap();
+    assert_eq!(reference, Ref::Reference("db_password".to_string()));
+
+    let registry =
+        HashMap::from([("db_password".to_string(), "hunter2".to_string())]);
+    assert_eq!(
+        reference.try_resolve_with_context(&registry).unwrap(),
+        "hunter2"
+    );
+}
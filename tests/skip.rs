@@ -0,0 +1,89 @@
+use std::rc::Rc;
+
+use enumify_struct::{enumify_struct, Applicable};
+
+use common::Plain;
+
+mod common;
+
+/// Not `Clone`, hence cannot be part of the generated structure.
+#[derive(Debug, Default, PartialEq)]
+struct Handle(u32);
+
+#[derive(Default)]
+#[enumify_struct(Plain)]
+struct Config {
+    name: String,
+    #[enumify_skip]
+    handle: Handle,
+    #[enumify_skip]
+    cache: Option<Rc<dyn Fn() -> u32>>,
+    // References are not supported, unless skipped
+    #[enumify_skip]
+    label: &'static str,
+    port: u16,
+}
+
+#[derive(Debug, Default, PartialEq)]
+#[enumify_struct(Plain)]
+struct Point(u32, #[enumify_skip] Handle, u32);
+
+#[derive(Debug, PartialEq)]
+#[enumify_struct(Plain)]
+enum Source {
+    File(String, #[enumify_skip] Handle),
+    Url {
+        #[enumify_skip]
+        handle: Handle,
+        url: String,
+    },
+}
+
+#[test]
+fn test_skipped_fields_are_untouched() {
+    let layer = EnumifiedConfig {
+        name: Plain::Value("app".to_string()),
+        port: Plain::Value(8080),
+    };
+
+    let base = layer.build(Config {
+        name: String::new(),
+        handle: Handle(7),
+        cache: Some(Rc::new(|| 42)),
+        label: "label",
+        port: 0,
+    });
+    assert_eq!(base.name, "app");
+    assert_eq!(base.handle, Handle(7));
+    assert_eq!(base.cache.map(|cache| cache()), Some(42));
+    assert_eq!(base.label, "label");
+    assert_eq!(base.port, 8080);
+}
+
+#[test]
+fn test_skipped_tuple_fields() {
+    let layer = EnumifiedPoint(Plain::Value(1), Plain::Value(2));
+    assert_eq!(layer.build(Point(0, Handle(7), 0)), Point(1, Handle(7), 2));
+}
+
+#[test]
+fn test_skipped_variant_fields() {
+    let mut base = Source::File("config.toml".to_string(), Handle(7));
+
+    EnumifiedSource::File(Plain::Value("other.toml".to_string()))
+        .apply_to(&mut base);
+    assert_eq!(base, Source::File("other.toml".to_string(), Handle(7)));
+
+    // Replacing the variant resets its skipped fields to their default
+    EnumifiedSource::Url {
+        url: Plain::Value("https://example.com".to_string()),
+    }
+    .apply_to(&mut base);
+    assert_eq!(
+        base,
+        Source::Url {
+            handle: Handle(0),
+            url: "https://example.com".to_string(),
+        }
+    );
+}
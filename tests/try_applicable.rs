@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+use enumify_struct::{
+    enumify_struct, Applicable, TryApplicable, TryResolveToBase,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Lookup<T> {
+    Value(T),
+    Key(String),
+}
+
+fn table() -> HashMap<&'static str, &'static str> {
+    HashMap::from([("port", "8080"), ("host", "localhost")])
+}
+
+impl<T: std::str::FromStr> TryResolveToBase<T> for Lookup<T> {
+    type Error = String;
+
+    fn try_resolve_to_base(self) -> Result<T, Self::Error> {
+        match self {
+            Lookup::Value(v) => Ok(v),
+            Lookup::Key(key) => table()
+                .get(key.as_str())
+                .ok_or_else(|| format!("unknown key `{key}`"))?
+                .parse()
+                .map_err(|_| format!("invalid value for key `{key}`")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Lookup)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[enumify_struct(Lookup)]
+struct Config {
+    name: String,
+    #[enumify_rename(EnumifiedServer)]
+    #[enumify_skip_wrap]
+    server: Server,
+}
+
+#[test]
+fn try_build_resolves_all_fields() {
+    let config = EnumifiedConfig {
+        name: Lookup::Value("app".to_string()),
+        server: EnumifiedServer {
+            host: Lookup::Key("host".to_string()),
+            port: Lookup::Key("port".to_string()),
+        },
+    };
+
+    let base = config.try_build(Config::default()).unwrap();
+    assert_eq!(
+        base,
+        Config {
+            name: "app".to_string(),
+            server: Server {
+                host: "localhost".to_string(),
+                port: 8080,
+            },
+        }
+    );
+}
+
+#[test]
+fn try_build_reports_dotted_path() {
+    let config = EnumifiedConfig {
+        name: Lookup::Value("app".to_string()),
+        server: EnumifiedServer {
+            host: Lookup::Key("host".to_string()),
+            port: Lookup::Key("missing".to_string()),
+        },
+    };
+
+    let err = config.try_build(Config::default()).unwrap_err();
+    assert_eq!(err.path(), "server.port");
+    assert_eq!(
+        err.to_string(),
+        "failed to resolve `server.port`: unknown key `missing`"
+    );
+}
+
+#[test]
+#[should_panic(expected = "unknown key `missing`")]
+fn apply_to_panics_on_failure() {
+    let config = EnumifiedConfig {
+        name: Lookup::Key("missing".to_string()),
+        server: EnumifiedServer {
+            host: Lookup::Key("host".to_string()),
+            port: Lookup::Key("port".to_string()),
+        },
+    };
+
+    config.build(Config::default());
+}
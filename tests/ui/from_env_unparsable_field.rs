@@ -0,0 +1,25 @@
+use std::str::FromStr;
+
+use enumify_struct::{enumify_struct, Layer};
+
+struct Tags;
+
+/// Not an `Error`, so that it cannot be reported
+struct InvalidTags;
+
+impl FromStr for Tags {
+    type Err = InvalidTags;
+
+    fn from_str(_: &str) -> Result<Self, Self::Err> {
+        Ok(Tags)
+    }
+}
+
+#[enumify_struct(Layer, derive())]
+#[enumify_from_env]
+struct Config {
+    name: String,
+    tags: Tags,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use enumify_struct::enumify_struct;
+
+struct Bar;
+
+#[enumify_struct(Option)]
+struct Foo {
+    #[enumify_rename(EnumifiedBar, Baz)]
+    bar: Bar,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use enumify_struct::enumify_struct;
+
+#[enumify_struct()]
+struct Foo {
+    bar: u8,
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use enumify_struct::{enumify_struct, ResolveToBase};
+
+#[derive(Clone)]
+enum Src<T> {
+    Value(T),
+}
+
+impl<T: Clone> ResolveToBase<T> for Src<T> {
+    fn resolve_to_base(&self) -> T {
+        match self {
+            Src::Value(v) => v.clone(),
+        }
+    }
+}
+
+#[enumify_struct(Src, derive())]
+struct Gen<T> {
+    x: T,
+}
+
+fn main() {}
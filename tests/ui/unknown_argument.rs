@@ -0,0 +1,13 @@
+use enumify_struct::enumify_struct;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Layer<T> {
+    Value(T),
+}
+
+#[enumify_struct(target = Layer, nmae = PartialConfig)]
+struct Config {
+    name: String,
+}
+
+fn main() {}
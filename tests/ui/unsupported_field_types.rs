@@ -0,0 +1,11 @@
+use enumify_struct::enumify_struct;
+
+#[enumify_struct(Option)]
+struct Foo {
+    a: impl Clone,
+    b: &'static str,
+    c: *const u8,
+    d: fn(),
+}
+
+fn main() {}
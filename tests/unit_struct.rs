@@ -0,0 +1,60 @@
+#![deny(warnings)]
+
+use enumify_struct::{enumify_struct, Applicable, TryApplicable};
+
+use common::Plain;
+
+mod common;
+
+#[derive(Debug, Default, PartialEq)]
+#[enumify_struct(Plain)]
+struct Marker;
+
+#[derive(Debug, Default, PartialEq)]
+#[enumify_struct(Plain)]
+struct Empty {}
+
+#[derive(Debug, Default, PartialEq)]
+#[enumify_struct(Plain)]
+struct EmptyTuple();
+
+#[derive(Debug, Default, PartialEq)]
+#[enumify_struct(Plain)]
+struct Gated {
+    #[cfg(any())]
+    hidden: String,
+}
+
+#[derive(Debug, Default, PartialEq)]
+#[enumify_struct(Plain)]
+struct Outer {
+    name: String,
+    #[enumify_nested]
+    marker: Marker,
+}
+
+#[test]
+fn test_unit_struct_is_a_no_op() {
+    let mut marker = Marker;
+    EnumifiedMarker.apply_to(&mut marker);
+    assert_eq!(marker, Marker);
+    assert_eq!(EnumifiedMarker.try_build(Marker).unwrap(), Marker);
+}
+
+#[test]
+fn test_empty_structs_are_no_ops() {
+    assert_eq!(EnumifiedEmpty {}.build(Empty {}), Empty {});
+    assert_eq!(EnumifiedEmptyTuple().build(EmptyTuple()), EmptyTuple());
+    assert_eq!(EnumifiedGated {}.build(Gated::default()), Gated {});
+}
+
+#[test]
+fn test_unit_struct_as_nested_field() {
+    let mut outer = Outer::default();
+    EnumifiedOuter {
+        name: Plain::Value("outer".to_string()),
+        marker: EnumifiedMarker,
+    }
+    .apply_to(&mut outer);
+    assert_eq!(outer.name, "outer");
+}